@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+/// Command line arguments: global options plus the remaining positional
+/// arguments.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub pokeapi: Option<PathBuf>,
+    /// A Showdown type chart to use instead of the built-in one.
+    pub chart: Option<PathBuf>,
+    /// Ruleset name, e.g. "go".
+    pub ruleset: Option<String>,
+    pub positional: Vec<String>,
+}
+
+impl Args {
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Args {
+        let mut parsed = Args::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--pokeapi" => parsed.pokeapi = args.next().map(PathBuf::from),
+                "--chart" => parsed.chart = args.next().map(PathBuf::from),
+                "--ruleset" => parsed.ruleset = args.next(),
+                _ => parsed.positional.push(arg),
+            }
+        }
+        parsed
+    }
+}
@@ -0,0 +1,263 @@
+use crate::chart::Chart;
+use crate::game::TypeGame;
+use crate::graph::EdgeKind;
+use crate::suggest::{rank_typings, typings, Scorer};
+use crate::types::{ChartType, Type};
+use std::collections::HashMap;
+
+/// Offensive and defensive standing of one type in a chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeBalance<T: ChartType = Type> {
+    pub ty: T,
+    /// Types its attacks hit super effectively.
+    pub super_effective: usize,
+    /// Types resisting its attacks.
+    pub resisted: usize,
+    /// Types immune to its attacks.
+    pub blocked: usize,
+    /// Attacking types it is weak to.
+    pub weaknesses: usize,
+    pub resistances: usize,
+    pub immunities: usize,
+    /// Mean log2 multiplier of its attacks against every mono and dual
+    /// typing, immunities counting as in `TypeGame`. Higher is better.
+    pub offense: f64,
+    /// Mean log2 multiplier the typings including it take from every
+    /// attacking type. Lower is better.
+    pub defense: f64,
+    /// 1-based rank of the best `list_types` team including it.
+    pub best_rank: usize,
+}
+
+/// Balance metrics of a chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Balance<T: ChartType = Type> {
+    pub types: Vec<TypeBalance<T>>,
+    /// Share of pairs of distinct types whose matchups mirror each other:
+    /// if one hits the other super effectively it is resisted or blocked
+    /// in return, and neutral pairs stay neutral both ways.
+    pub symmetry: f64,
+    /// Types as `(dominated, by)`: `by` hits everything at least as hard
+    /// and takes nothing harder, and differs somewhere.
+    pub dominated: Vec<(T, T)>,
+    /// Shannon entropy in bits of the chart's multipliers.
+    pub entropy: f64,
+}
+
+impl<T: ChartType> Balance<T> {
+    pub fn new(chart: &Chart<T>) -> Self {
+        let game = TypeGame::new();
+        let all = typings(chart);
+        let ranking = rank_typings(chart, chart.types().len().min(3), Scorer::LogRates);
+
+        let types = chart
+            .types()
+            .iter()
+            .map(|ty| {
+                let count = |rates: Vec<f64>, kind| {
+                    rates
+                        .iter()
+                        .filter(|rate| EdgeKind::of(**rate) == Some(kind))
+                        .count()
+                };
+                let attack: Vec<_> = chart.types().iter().map(|b| chart.combat1(ty, b)).collect();
+                let block: Vec<_> = chart.types().iter().map(|a| chart.combat1(a, ty)).collect();
+                let offense = mean(
+                    all.iter()
+                        .map(|types| game.payoff(chart.combat_n(ty, types))),
+                );
+                let defense = mean(
+                    all.iter()
+                        .filter(|types| types.contains(ty))
+                        .flat_map(|types| chart.types().iter().map(move |a| (a, types)))
+                        .map(|(a, types)| game.payoff(chart.combat_n(a, types))),
+                );
+                TypeBalance {
+                    ty: *ty,
+                    super_effective: count(attack.clone(), EdgeKind::SuperEffective),
+                    resisted: count(attack.clone(), EdgeKind::Resisted),
+                    blocked: count(attack, EdgeKind::Immune),
+                    weaknesses: count(block.clone(), EdgeKind::SuperEffective),
+                    resistances: count(block.clone(), EdgeKind::Resisted),
+                    immunities: count(block, EdgeKind::Immune),
+                    offense,
+                    defense,
+                    best_rank: ranking
+                        .iter()
+                        .position(|(types, _)| types.contains(ty))
+                        .map_or(0, |i| i + 1),
+                }
+            })
+            .collect();
+
+        Self {
+            types,
+            symmetry: symmetry(chart),
+            dominated: dominated(chart),
+            entropy: entropy(chart),
+        }
+    }
+
+    pub fn get(&self, ty: &T) -> Option<&TypeBalance<T>> {
+        self.types.iter().find(|balance| balance.ty == *ty)
+    }
+
+    /// How each type present in both charts changed from `self` to
+    /// `other`, most strengthened first.
+    pub fn diff(&self, other: &Balance<T>) -> Vec<BalanceChange<T>> {
+        let mut changes: Vec<_> = self
+            .types
+            .iter()
+            .filter_map(|old| {
+                let new = other.get(&old.ty)?;
+                Some(BalanceChange {
+                    ty: old.ty,
+                    offense: new.offense - old.offense,
+                    defense: new.defense - old.defense,
+                    best_rank: new.best_rank as i64 - old.best_rank as i64,
+                })
+            })
+            .collect();
+        changes.sort_by(|a, b| b.strength().partial_cmp(&a.strength()).unwrap());
+        changes
+    }
+}
+
+/// Change of one type's metrics between two charts, as new minus old.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceChange<T: ChartType = Type> {
+    pub ty: T,
+    pub offense: f64,
+    pub defense: f64,
+    pub best_rank: i64,
+}
+
+impl<T: ChartType> BalanceChange<T> {
+    /// Offense gained plus defense gained; positive if the type got
+    /// stronger.
+    pub fn strength(&self) -> f64 {
+        self.offense - self.defense
+    }
+}
+
+fn mean<I: Iterator<Item = f64>>(values: I) -> f64 {
+    let (sum, n) = values.fold((0.0, 0), |(sum, n), value| (sum + value, n + 1));
+    if n == 0 {
+        0.0
+    } else {
+        sum / n as f64
+    }
+}
+
+/// -1, 0 or 1 for a resisted or blocked, neutral or super-effective hit.
+fn sign(rate: f64) -> i8 {
+    match EdgeKind::of(rate) {
+        Some(EdgeKind::SuperEffective) => 1,
+        Some(_) => -1,
+        None => 0,
+    }
+}
+
+/// `Balance::symmetry` without the per-type metrics.
+pub fn symmetry<T: ChartType>(chart: &Chart<T>) -> f64 {
+    let types = chart.types();
+    let (mut mirrored, mut pairs) = (0, 0);
+    for (i, a) in types.iter().enumerate() {
+        for b in &types[i + 1..] {
+            pairs += 1;
+            if sign(chart.combat1(a, b)) == -sign(chart.combat1(b, a)) {
+                mirrored += 1;
+            }
+        }
+    }
+    if pairs == 0 {
+        1.0
+    } else {
+        mirrored as f64 / pairs as f64
+    }
+}
+
+/// `Balance::dominated` without the per-type metrics.
+pub fn dominated<T: ChartType>(chart: &Chart<T>) -> Vec<(T, T)> {
+    let types = chart.types();
+    let mut dominated = Vec::new();
+    for a in types {
+        let by = types.iter().find(|b| {
+            let mut strict = false;
+            for x in types {
+                let (attack_a, attack_b) = (chart.combat1(a, x), chart.combat1(b, x));
+                let (block_a, block_b) = (chart.combat1(x, a), chart.combat1(x, b));
+                if attack_b < attack_a || block_b > block_a {
+                    return false;
+                }
+                strict |= attack_b > attack_a || block_b < block_a;
+            }
+            strict
+        });
+        if let Some(by) = by {
+            dominated.push((*a, *by));
+        }
+    }
+    dominated
+}
+
+fn entropy<T: ChartType>(chart: &Chart<T>) -> f64 {
+    let mut counts: HashMap<u64, usize> = HashMap::new();
+    for a in chart.types() {
+        for b in chart.types() {
+            *counts.entry(chart.combat1(a, b).to_bits()).or_default() += 1;
+        }
+    }
+    let total = counts.values().sum::<usize>() as f64;
+    -counts
+        .values()
+        .map(|n| {
+            let p = *n as f64 / total;
+            p * p.log2()
+        })
+        .sum::<f64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+
+    #[test]
+    fn builtin() {
+        let balance = Balance::new(&Chart::builtin());
+        let normal = balance.get(&Normal).unwrap();
+        assert_eq!(
+            (normal.super_effective, normal.resisted, normal.blocked),
+            (0, 2, 1)
+        );
+        let ghost = balance.get(&Ghost).unwrap();
+        assert_eq!((ghost.weaknesses, ghost.immunities), (2, 2));
+        assert_eq!(balance.get(&Flying).unwrap().best_rank, 1);
+        assert!(balance.get(&Normal).unwrap().offense < balance.get(&Ground).unwrap().offense);
+        assert!(balance.symmetry > 0.0 && balance.symmetry < 1.0);
+        assert!(balance.entropy > 1.0 && balance.entropy < 2.0);
+    }
+
+    #[test]
+    fn dominated_types() {
+        let mut chart = Chart::neutral(&[Fire, Water]);
+        chart.set(&Water, &Fire, 2.0);
+        let balance = Balance::new(&chart);
+        assert_eq!(balance.dominated, vec![(Fire, Water)]);
+        assert_eq!(balance.symmetry, 0.0);
+    }
+
+    #[test]
+    fn diff_steel_nerf() {
+        let mut gen5 = Chart::builtin();
+        gen5.set(&Ghost, &Steel, 0.5);
+        gen5.set(&Dark, &Steel, 0.5);
+        let changes = Balance::new(&gen5).diff(&Balance::new(&Chart::builtin()));
+        assert_eq!(changes.len(), 18);
+        let steel = changes.iter().find(|c| c.ty == Steel).unwrap();
+        assert!(steel.defense > 0.0);
+        assert_eq!(changes.last().unwrap().ty, Steel);
+        assert!(changes[..2].iter().all(|c| c.ty == Ghost || c.ty == Dark));
+    }
+}
@@ -0,0 +1,377 @@
+use crate::generation::Generation;
+use crate::ruleset::{Ruleset, Stacking};
+use crate::types::{BlockTypes, ChartType, CustomType, Localization, Type, TYPE_COUNT};
+use std::collections::HashMap;
+
+/// A type effectiveness chart. The associated functions on `Type` use the
+/// built-in Gen 6+ chart; a `Chart` can also hold an earlier generation's
+/// chart or one loaded from data files, and be read under another game's
+/// ruleset. Charts over `CustomType` can hold any set of named types.
+#[derive(Debug, Clone)]
+pub struct Chart<T: ChartType = Type> {
+    types: Vec<T>,
+    /// Side of the square `effect` matrix, indexed by `ChartType::index`.
+    size: usize,
+    effect: Vec<f64>,
+    ruleset: Ruleset,
+    identifiers: HashMap<T, String>,
+    /// Localized names keyed by type and language, e.g. "ja".
+    names: HashMap<T, HashMap<String, String>>,
+}
+
+impl Default for Chart {
+    fn default() -> Self {
+        Chart::builtin()
+    }
+}
+
+/// Charts are equal when they have the same types and ruleset and agree
+/// on every matchup between them.
+impl<T: ChartType> PartialEq for Chart<T> {
+    fn eq(&self, other: &Chart<T>) -> bool {
+        self.types == other.types
+            && self.ruleset == other.ruleset
+            && self.mismatches(other).is_empty()
+    }
+}
+
+impl Chart {
+    /// The built-in Gen 6+ chart.
+    pub fn builtin() -> Self {
+        let mut chart = Chart::neutral(&Type::types());
+        for attack_type in Type::types().iter() {
+            for block_type in Type::types().iter() {
+                chart.set(
+                    attack_type,
+                    block_type,
+                    Type::combat1(attack_type, block_type),
+                );
+            }
+        }
+        chart
+    }
+
+    /// The built-in chart as it was in the given generation.
+    pub fn generation(generation: Generation) -> Self {
+        use Type::*;
+        let mut chart = Chart::builtin();
+        chart.types = generation.types();
+        if generation <= Generation::Gen5 {
+            chart.set(&Ghost, &Steel, 0.5);
+            chart.set(&Dark, &Steel, 0.5);
+        }
+        if generation == Generation::Gen1 {
+            chart.set(&Bug, &Poison, 2.0);
+            chart.set(&Poison, &Bug, 2.0);
+            chart.set(&Ghost, &Psychic, 0.0);
+            chart.set(&Ice, &Fire, 1.0);
+        }
+        chart
+    }
+}
+
+impl<T: ChartType> Chart<T> {
+    /// A chart over the given types where every matchup is neutral.
+    pub fn neutral(types: &[T]) -> Self {
+        let size = types
+            .iter()
+            .map(|ty| ty.index() + 1)
+            .max()
+            .unwrap_or(0)
+            .max(TYPE_COUNT);
+        Self {
+            types: types.to_vec(),
+            size,
+            effect: vec![1.0; size * size],
+            ruleset: Ruleset::default(),
+            identifiers: HashMap::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Types that exist in this chart.
+    pub fn types(&self) -> &[T] {
+        &self.types
+    }
+
+    pub fn has(&self, ty: &T) -> bool {
+        self.types.contains(ty)
+    }
+
+    /// The same relationships read under another ruleset.
+    pub fn with_ruleset(mut self, ruleset: Ruleset) -> Self {
+        self.ruleset = ruleset;
+        self
+    }
+
+    pub fn ruleset(&self) -> &Ruleset {
+        &self.ruleset
+    }
+
+    /// Lowercase identifier such as "fighting".
+    pub fn identifier(&self, ty: &T) -> String {
+        match (self.identifiers.get(ty), ty.builtin()) {
+            (Some(identifier), _) => identifier.clone(),
+            (None, Some(builtin)) => builtin.identifier().to_string(),
+            (None, None) => format!("type-{}", ty.index()),
+        }
+    }
+
+    /// Name of the type in the given language, falling back to the
+    /// built-in names, the English name and then the identifier.
+    pub fn name(&self, ty: &T, language: &str) -> String {
+        if let Some(name) = self.names.get(ty).and_then(|names| names.get(language)) {
+            return name.clone();
+        }
+        match (ty.builtin(), Localization::from_code(language)) {
+            (Some(builtin), Some(l10n)) => builtin.to_str(&l10n).to_string(),
+            _ => match self.names.get(ty).and_then(|names| names.get("en")) {
+                Some(name) => name.clone(),
+                None => self.identifier(ty),
+            },
+        }
+    }
+
+    pub fn set_name(&mut self, ty: &T, language: &str, name: &str) {
+        self.names
+            .entry(*ty)
+            .or_default()
+            .insert(language.to_string(), name.to_string());
+    }
+
+    /// Finds a type by identifier or by any of its names, ignoring case.
+    pub fn find(&self, name: &str) -> Option<T> {
+        self.types.iter().copied().find(|ty| {
+            self.identifier(ty).eq_ignore_ascii_case(name)
+                || self
+                    .names
+                    .get(ty)
+                    .is_some_and(|names| names.values().any(|n| n.eq_ignore_ascii_case(name)))
+                || ty.builtin().is_some_and(|builtin| {
+                    Localization::localizations()
+                        .iter()
+                        .any(|l10n| Type::from_localized(name, l10n) == Some(builtin))
+                })
+        })
+    }
+
+    /// Sets the mainline multiplier of a matchup.
+    pub fn set(&mut self, attack_type: &T, block_type: &T, rate: f64) {
+        self.effect[attack_type.index() * self.size + block_type.index()] = rate;
+    }
+
+    /// The mainline multiplier of a matchup, whatever the ruleset.
+    pub fn relation(&self, attack_type: &T, block_type: &T) -> f64 {
+        self.effect[attack_type.index() * self.size + block_type.index()]
+    }
+
+    pub fn combat1(&self, attack_type: &T, block_type: &T) -> f64 {
+        self.ruleset
+            .multiplier(self.relation(attack_type, block_type))
+    }
+
+    pub fn combat_n<B: BlockTypes<T> + ?Sized>(&self, attack_type: &T, block_types: &B) -> f64 {
+        let relations: Vec<_> = block_types
+            .block_types()
+            .iter()
+            .map(|block_type| self.relation(attack_type, block_type))
+            .collect();
+        self.ruleset.stack(&relations)
+    }
+
+    pub fn attack_rates(&self, attack_type: &T) -> HashMap<T, f64> {
+        self.types
+            .iter()
+            .map(|block_type| (*block_type, self.combat1(attack_type, block_type)))
+            .collect()
+    }
+
+    pub fn block_rates(&self, block_type: &T) -> HashMap<T, f64> {
+        self.types
+            .iter()
+            .map(|attack_type| (*attack_type, self.combat1(attack_type, block_type)))
+            .collect()
+    }
+
+    pub fn blocks_rates<B: BlockTypes<T> + ?Sized>(&self, block_types: &B) -> HashMap<T, f64> {
+        let block_types = block_types.block_types();
+        self.types
+            .iter()
+            .map(|attack_type| (*attack_type, self.combat_n(attack_type, &block_types)))
+            .collect()
+    }
+
+    /// The chart of an Inverse Battle: weaknesses and resistances swap, and
+    /// immunities become weaknesses. Inverting twice gives back the chart,
+    /// except that immunities come back as resistances.
+    pub fn inverse(&self) -> Self {
+        let mut inverse = self.clone();
+        for rate in &mut inverse.effect {
+            *rate = if *rate == 0.0 { 2.0 } else { 1.0 / *rate };
+        }
+        inverse
+    }
+
+    /// Matchups whose multiplier differs from `other`, as
+    /// `(attack, block, self, other)`. Only types in both charts are
+    /// compared.
+    pub fn mismatches(&self, other: &Chart<T>) -> Vec<(T, T, f64, f64)> {
+        let mut mismatches = Vec::new();
+        for attack_type in self.types.iter().filter(|ty| other.has(ty)) {
+            for block_type in self.types.iter().filter(|ty| other.has(ty)) {
+                let (a, b) = (
+                    self.combat1(attack_type, block_type),
+                    other.combat1(attack_type, block_type),
+                );
+                if a != b {
+                    mismatches.push((*attack_type, *block_type, a, b));
+                }
+            }
+        }
+        mismatches
+    }
+}
+
+impl Chart<CustomType> {
+    /// A custom chart starting from the types and matchups of a built-in
+    /// one, under the same ruleset.
+    pub fn from_builtin(chart: &Chart) -> Self {
+        let types: Vec<_> = chart.types().iter().map(CustomType::from).collect();
+        let mut custom = Chart::neutral(&types).with_ruleset(chart.ruleset().clone());
+        for a in chart.types() {
+            for b in chart.types() {
+                custom.set(&a.into(), &b.into(), chart.relation(a, b));
+            }
+        }
+        custom
+    }
+
+    /// Adds a type that is neutral to and from every other type, or
+    /// returns the existing one with that identifier.
+    pub fn add_type(&mut self, identifier: &str) -> CustomType {
+        if let Some(ty) = self.find(identifier) {
+            return ty;
+        }
+        let ty = CustomType(self.size as u16);
+        let size = self.size + 1;
+        let mut effect = vec![1.0; size * size];
+        for row in 0..self.size {
+            effect[row * size..row * size + self.size]
+                .copy_from_slice(&self.effect[row * self.size..(row + 1) * self.size]);
+        }
+        self.size = size;
+        self.effect = effect;
+        self.types.push(ty);
+        self.identifiers.insert(ty, identifier.to_string());
+        ty
+    }
+
+    /// Adds the ??? type of Curse in Gen 2 to 4: neutral both ways.
+    pub fn with_unknown(mut self) -> Self {
+        let unknown = self.add_type("unknown");
+        self.set_name(&unknown, "en", "???");
+        self.set_name(&unknown, "ja", "???");
+        self
+    }
+
+    /// Adds the Shadow type of Colosseum and XD: super effective against
+    /// every other type and resisted by itself.
+    pub fn with_shadow(mut self) -> Self {
+        let shadow = self.add_type("shadow");
+        self.set_name(&shadow, "en", "Shadow");
+        self.set_name(&shadow, "ja", "ダーク");
+        for ty in self.types.clone() {
+            self.set(&shadow, &ty, if ty == shadow { 0.5 } else { 2.0 });
+        }
+        self
+    }
+
+    /// Adds Stellar, the Tera type that keeps the original typing on
+    /// defense, so it is neutral both ways in a chart.
+    pub fn with_stellar(mut self) -> Self {
+        let stellar = self.add_type("stellar");
+        self.set_name(&stellar, "en", "Stellar");
+        self.set_name(&stellar, "ja", "ステラ");
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+
+    #[test]
+    fn builtin_matches_type_effect() {
+        let chart = Chart::builtin();
+        for a in Type::types().iter() {
+            for b in Type::types().iter() {
+                assert_eq!(chart.combat1(a, b), Type::combat1(a, b));
+            }
+        }
+        assert_eq!(
+            chart.blocks_rates(&[&Water, &Ground]),
+            Type::blocks_rates(&[&Water, &Ground])
+        );
+    }
+
+    #[test]
+    fn past_generations() {
+        let gen5 = Chart::generation(Generation::Gen5);
+        assert_eq!(gen5.types().len(), 17);
+        assert!(!gen5.has(&Fairy));
+        assert_eq!(gen5.combat1(&Ghost, &Steel), 0.5);
+        assert_eq!(gen5.mismatches(&Chart::builtin()).len(), 2);
+
+        let gen1 = Chart::generation(Generation::Gen1);
+        assert_eq!(gen1.combat1(&Ghost, &Psychic), 0.0);
+        assert_eq!(gen1.combat_n(&Bug, &[&Poison, &Grass]), 4.0);
+        assert_eq!(gen1.mismatches(&Chart::builtin()).len(), 4);
+    }
+
+    #[test]
+    fn go_ruleset() {
+        let go = Chart::builtin().with_ruleset(Ruleset::Go);
+        assert_eq!(go.combat1(&Normal, &Ghost), 0.390625);
+        assert_eq!(go.relation(&Normal, &Ghost), 0.0);
+        assert!((go.combat_n(&Ice, &[&Dragon, &Ground]) - 2.56).abs() < 1e-12);
+        assert_eq!(go.combat_n(&Electric, &[&Water, &Ground]), 1.6 * 0.390625);
+        assert_ne!(go, Chart::builtin());
+
+        let arceus = Chart::builtin().with_ruleset(Ruleset::LegendsArceus);
+        assert_eq!(arceus.combat_n(&Ice, &[&Dragon, &Ground]), 2.5);
+        assert_eq!(arceus.combat1(&Ice, &Dragon), 2.0);
+    }
+
+    #[test]
+    fn custom_types() {
+        let chart = Chart::from_builtin(&Chart::builtin())
+            .with_unknown()
+            .with_shadow();
+        assert_eq!(chart.types().len(), 20);
+        let (fire, water) = (CustomType::from(Fire), CustomType::from(Water));
+        assert_eq!(chart.combat1(&fire, &water), 0.5);
+
+        let unknown = chart.find("???").unwrap();
+        let shadow = chart.find("ダーク").unwrap();
+        assert_eq!(chart.identifier(&shadow), "shadow");
+        assert_eq!(chart.name(&fire, "ja"), "炎");
+        assert_eq!(chart.name(&unknown, "de"), "???");
+        assert_eq!(chart.combat1(&shadow, &unknown), 2.0);
+        assert_eq!(chart.combat1(&shadow, &shadow), 0.5);
+        assert_eq!(chart.combat_n(&shadow, &[&fire, &water]), 4.0);
+        assert_eq!(chart.combat1(&fire, &shadow), 1.0);
+    }
+
+    #[test]
+    fn analyses_agree_over_custom_types() {
+        use crate::suggest::{rank_typings, Scorer};
+        let builtin = Chart::builtin();
+        let custom = Chart::from_builtin(&builtin);
+        let expected: Vec<_> = rank_typings(&builtin, 2, Scorer::Holes)
+            .into_iter()
+            .map(|(types, score)| (types.iter().map(CustomType::from).collect(), score))
+            .collect();
+        assert_eq!(rank_typings(&custom, 2, Scorer::Holes), expected);
+    }
+}
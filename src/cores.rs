@@ -0,0 +1,191 @@
+use crate::chart::Chart;
+use crate::suggest::typings;
+use crate::types::{ChartType, Type};
+use itertools::Itertools;
+
+/// Typings that cover each other's weaknesses, like Fire/Water/Grass.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Core<T: ChartType = Type> {
+    pub members: Vec<Vec<T>>,
+    /// Weaknesses, as member index and attacking type, that no other
+    /// member resists. Empty unless the search allows them.
+    pub uncovered: Vec<(usize, T)>,
+    /// Mean of the best multiplier another member takes from each covered
+    /// weakness: 0 when every weakness meets an immunity, up to just
+    /// under 1. Lower is tighter.
+    pub tightness: f64,
+    /// Stealth Rock penalty, 0 unless enabled.
+    pub penalty: f64,
+}
+
+impl<T: ChartType> Core<T> {
+    /// Uncovered weaknesses count one each on top of the tightness.
+    pub fn score(&self) -> f64 {
+        self.uncovered.len() as f64 + self.tightness + self.penalty
+    }
+}
+
+/// Search for defensive cores of a given size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreSearch {
+    size: usize,
+    uncovered: usize,
+    stealth_rock: f64,
+}
+
+impl Default for CoreSearch {
+    fn default() -> Self {
+        CoreSearch::new(3)
+    }
+}
+
+impl CoreSearch {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            uncovered: 0,
+            stealth_rock: 0.0,
+        }
+    }
+
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Also accepts sets with up to `n` weaknesses no other member
+    /// resists, so near-cores like Fire/Water/Grass show up.
+    pub fn allow_uncovered(mut self, n: usize) -> Self {
+        self.uncovered = n;
+        self
+    }
+
+    /// Adds `weight` to a core's score for every member weak to Stealth
+    /// Rock, twice for a 4× weakness.
+    pub fn stealth_rock(mut self, weight: f64) -> Self {
+        self.stealth_rock = weight;
+        self
+    }
+
+    /// Every core over the chart's mono and dual typings, tightest first.
+    pub fn find<T: ChartType>(&self, chart: &Chart<T>) -> Vec<Core<T>> {
+        let typings = typings(chart);
+        // Multipliers per typing, indexed like `chart.types()`.
+        let rates: Vec<Vec<f64>> = typings
+            .iter()
+            .map(|types| {
+                chart
+                    .types()
+                    .iter()
+                    .map(|ty| chart.combat_n(ty, types))
+                    .collect()
+            })
+            .collect();
+        let weaknesses: Vec<Vec<usize>> = rates
+            .iter()
+            .map(|rates| (0..rates.len()).filter(|i| rates[*i] > 1.0).collect())
+            .collect();
+        let rock = chart
+            .types()
+            .iter()
+            .position(|ty| ty.builtin() == Some(Type::Rock));
+
+        let mut cores: Vec<_> = (0..typings.len())
+            .combinations(self.size)
+            .filter_map(|members| {
+                let mut covers = Vec::new();
+                let mut uncovered = Vec::new();
+                for (i, &member) in members.iter().enumerate() {
+                    for weakness in &weaknesses[member] {
+                        let best = members
+                            .iter()
+                            .filter(|other| **other != member)
+                            .map(|other| rates[*other][*weakness])
+                            .fold(f64::INFINITY, f64::min);
+                        if best < 1.0 {
+                            covers.push(best);
+                        } else if uncovered.len() < self.uncovered {
+                            uncovered.push((i, chart.types()[*weakness]));
+                        } else {
+                            return None;
+                        }
+                    }
+                }
+                let tightness = if covers.is_empty() {
+                    0.0
+                } else {
+                    covers.iter().sum::<f64>() / covers.len() as f64
+                };
+                let penalty = match rock {
+                    Some(rock) => {
+                        members
+                            .iter()
+                            .map(|member| match rates[*member][rock] {
+                                rate if rate >= 4.0 => 2.0,
+                                rate if rate > 1.0 => 1.0,
+                                _ => 0.0,
+                            })
+                            .sum::<f64>()
+                            * self.stealth_rock
+                    }
+                    None => 0.0,
+                };
+                Some(Core {
+                    members: members.iter().map(|i| typings[*i].clone()).collect(),
+                    uncovered,
+                    tightness,
+                    penalty,
+                })
+            })
+            .collect();
+        cores.sort_by(|a, b| a.score().partial_cmp(&b.score()).unwrap());
+        cores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+
+    fn is_core(chart: &Chart, core: &Core) -> bool {
+        core.members.iter().enumerate().all(|(i, member)| {
+            chart.types().iter().all(|ty| {
+                chart.combat_n(ty, member) <= 1.0
+                    || core
+                        .members
+                        .iter()
+                        .enumerate()
+                        .any(|(j, other)| i != j && chart.combat_n(ty, other) < 1.0)
+            })
+        })
+    }
+
+    #[test]
+    fn pairs() {
+        let chart = Chart::builtin();
+        let cores = CoreSearch::new(2).find(&chart);
+        assert!(!cores.is_empty());
+        assert!(cores.iter().all(|core| is_core(&chart, core)));
+        assert!(!cores
+            .iter()
+            .any(|core| core.members == vec![vec![Fire], vec![Water]]));
+    }
+
+    #[test]
+    fn fire_water_grass() {
+        let chart = Chart::builtin();
+        let cores = CoreSearch::new(3)
+            .allow_uncovered(3)
+            .stealth_rock(0.5)
+            .find(&chart);
+        let fwg = cores
+            .iter()
+            .find(|core| core.members == vec![vec![Fire], vec![Water], vec![Grass]])
+            .unwrap();
+        assert_eq!(fwg.uncovered, vec![(0, Rock), (2, Flying), (2, Poison)]);
+        assert_eq!(fwg.tightness, 0.5);
+        assert_eq!(fwg.penalty, 0.5);
+        assert!(cores.iter().all(|core| core.score() >= cores[0].score()));
+    }
+}
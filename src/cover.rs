@@ -0,0 +1,186 @@
+use crate::chart::Chart;
+use crate::generation::Generation;
+use crate::species::Pokedex;
+use crate::suggest::typings;
+use crate::types::{ChartType, Type};
+use itertools::Itertools;
+
+/// Smallest sets of attacking types that hit every typing at or above a
+/// multiplier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cover<T: ChartType = Type> {
+    /// Every optimal set, in chart order.
+    pub solutions: Vec<Vec<T>>,
+    /// Typings no attacking type reaches the threshold against; they are
+    /// left out of the search.
+    pub uncoverable: Vec<Vec<T>>,
+}
+
+impl<T: ChartType> Cover<T> {
+    /// Size of the optimal sets, or `None` when nothing can be covered.
+    pub fn size(&self) -> Option<usize> {
+        self.solutions.first().map(Vec::len)
+    }
+}
+
+/// Exact set cover of typings by attacking types.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverSearch<T: ChartType = Type> {
+    threshold: f64,
+    typings: Option<Vec<Vec<T>>>,
+}
+
+impl<T: ChartType> Default for CoverSearch<T> {
+    fn default() -> Self {
+        CoverSearch::new()
+    }
+}
+
+impl<T: ChartType> CoverSearch<T> {
+    /// Covers every mono and dual typing super effectively.
+    pub fn new() -> Self {
+        Self {
+            threshold: 2.0,
+            typings: None,
+        }
+    }
+
+    /// The multiplier each typing must be hit with, e.g. 1.0 for at least
+    /// neutral. It is a mainline multiplier, so 2.0 still means super
+    /// effective under another ruleset.
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Covers only the given typings instead of all of the chart's.
+    pub fn typings(mut self, typings: Vec<Vec<T>>) -> Self {
+        self.typings = Some(typings);
+        self
+    }
+
+    pub fn solve(&self, chart: &Chart<T>) -> Cover<T> {
+        let all = typings(chart);
+        let typings = self.typings.as_ref().unwrap_or(&all);
+        let attack_types = chart.types();
+        let threshold = chart.ruleset().multiplier(self.threshold);
+
+        // For every attacking type, a bit set of the typings it covers.
+        let words = typings.len().div_ceil(64);
+        let hits: Vec<Vec<u64>> = attack_types
+            .iter()
+            .map(|attack_type| {
+                let mut bits = vec![0; words];
+                for (i, types) in typings.iter().enumerate() {
+                    if chart.combat_n(attack_type, types) >= threshold {
+                        bits[i / 64] |= 1 << (i % 64);
+                    }
+                }
+                bits
+            })
+            .collect();
+        let mut coverable = vec![0; words];
+        for bits in &hits {
+            for (word, bit) in coverable.iter_mut().zip(bits) {
+                *word |= bit;
+            }
+        }
+        let uncoverable = typings
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| coverable[i / 64] & (1 << (i % 64)) == 0)
+            .map(|(_, types)| types.clone())
+            .collect();
+
+        let mut solutions = Vec::new();
+        if coverable.iter().any(|word| *word != 0) {
+            for size in 1..=attack_types.len() {
+                for set in (0..attack_types.len()).combinations(size) {
+                    let covers = (0..words)
+                        .all(|w| set.iter().fold(0, |acc, i| acc | hits[*i][w]) == coverable[w]);
+                    if covers {
+                        solutions.push(set.iter().map(|i| attack_types[*i]).collect());
+                    }
+                }
+                if !solutions.is_empty() {
+                    break;
+                }
+            }
+        }
+        Cover {
+            solutions,
+            uncoverable,
+        }
+    }
+}
+
+impl CoverSearch {
+    /// Covers only typings that species of the generation have.
+    pub fn species(self, dex: &Pokedex, generation: Generation) -> Self {
+        let typings = dex
+            .species()
+            .iter()
+            .filter(|species| species.generation <= generation)
+            .filter_map(|species| species.types_in(generation))
+            .map(|mut types| {
+                types.sort_by_key(|ty| *ty as usize);
+                types
+            })
+            .unique()
+            .collect();
+        self.typings(typings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ruleset::Ruleset;
+    use crate::types::Type::*;
+
+    #[test]
+    fn neutral_cover() {
+        let cover = CoverSearch::new().threshold(1.0).solve(&Chart::builtin());
+        assert_eq!(cover.uncoverable, Vec::<Vec<Type>>::new());
+        assert_eq!(cover.size(), Some(3));
+        assert_eq!(cover.solutions.len(), 97);
+        assert!(cover.solutions.contains(&vec![Fight, Ghost, Fairy]));
+    }
+
+    #[test]
+    fn super_effective_cover() {
+        let chart = Chart::builtin();
+        let cover = CoverSearch::new().solve(&chart);
+        let size = cover.size().unwrap();
+        for solution in &cover.solutions {
+            assert_eq!(solution.len(), size);
+            for types in typings(&chart) {
+                assert!(
+                    cover.uncoverable.contains(&types)
+                        || solution.iter().any(|ty| chart.combat_n(ty, &types) >= 2.0)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn go_threshold() {
+        let chart = Chart::builtin();
+        assert_eq!(
+            CoverSearch::new().solve(&chart.clone().with_ruleset(Ruleset::Go)),
+            CoverSearch::new().solve(&chart)
+        );
+    }
+
+    #[test]
+    fn gen4_species_with_no_weakness() {
+        let chart = Chart::generation(Generation::Gen4);
+        let cover = CoverSearch::new()
+            .typings(vec![vec![Ghost, Dark], vec![Water], vec![Fire]])
+            .solve(&chart);
+        assert_eq!(cover.uncoverable, vec![vec![Ghost, Dark]]);
+        assert_eq!(cover.size(), Some(2));
+        assert_eq!(cover.solutions.len(), 6);
+        assert!(cover.solutions.contains(&vec![Ground, Electric]));
+    }
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A CSV file with a header row, as found in the PokeAPI data dumps.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Table {
+    header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn read(path: &Path) -> io::Result<Table> {
+        let text = fs::read_to_string(path)?;
+        Table::parse(&text).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: missing header", path.display()),
+            )
+        })
+    }
+
+    pub fn parse(text: &str) -> Option<Table> {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let header = split_line(lines.next()?);
+        let rows = lines.map(split_line).collect();
+        Some(Table { header, rows })
+    }
+
+    pub fn column(&self, name: &str) -> io::Result<usize> {
+        self.header.iter().position(|h| h == name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("missing column `{}`", name),
+            )
+        })
+    }
+}
+
+/// Splits one line, honouring double-quoted fields.
+fn split_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+pub(crate) fn parse_number<T: std::str::FromStr>(s: &str) -> io::Result<T> {
+    s.trim().parse().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid number `{}`", s),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_fields() {
+        let table = Table::parse("id,name\n1,\"Mr. \"\"Mime\"\", the\"\n").unwrap();
+        assert_eq!(table.column("name").unwrap(), 1);
+        assert_eq!(table.rows, vec![vec!["1", "Mr. \"Mime\", the"]]);
+    }
+}
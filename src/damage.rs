@@ -0,0 +1,411 @@
+use crate::chart::Chart;
+use crate::field::Weather;
+use crate::generation::Generation;
+use crate::immunity::{type_rules, TypeRule};
+use crate::stats::{Spread, Stat};
+use crate::types::Type;
+use std::collections::HashMap;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Category {
+    Physical,
+    Special,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Move {
+    pub ty: Type,
+    pub power: u32,
+    pub category: Category,
+}
+
+impl Move {
+    pub fn new(ty: Type, power: u32, category: Category) -> Self {
+        Self {
+            ty,
+            power,
+            category,
+        }
+    }
+}
+
+/// A Pokémon in battle, as far as the damage formula is concerned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pokemon {
+    pub level: u32,
+    pub types: Vec<Type>,
+    pub stats: [u32; 6],
+    pub tera: Option<Type>,
+    pub adaptability: bool,
+    pub burned: bool,
+}
+
+impl Pokemon {
+    pub fn new(types: &[&Type], spread: &Spread) -> Self {
+        Self {
+            level: spread.level,
+            types: types.iter().map(|ty| **ty).collect(),
+            stats: spread.stats(),
+            tera: None,
+            adaptability: false,
+            burned: false,
+        }
+    }
+
+    pub fn tera(mut self, ty: Type) -> Self {
+        self.tera = Some(ty);
+        self
+    }
+
+    pub fn adaptability(mut self) -> Self {
+        self.adaptability = true;
+        self
+    }
+
+    pub fn burned(mut self) -> Self {
+        self.burned = true;
+        self
+    }
+
+    pub fn stat(&self, stat: Stat) -> u32 {
+        self.stats[stat.index()]
+    }
+
+    /// Types used when being hit; a Terastallized Pokémon only has its
+    /// Tera type.
+    pub fn defensive_types(&self) -> Vec<&Type> {
+        match &self.tera {
+            Some(tera) => vec![tera],
+            None => self.types.iter().collect(),
+        }
+    }
+
+    /// Same-type attack bonus as a multiple of 4096.
+    pub fn stab(&self, move_type: &Type) -> u32 {
+        let original = self.types.contains(move_type);
+        match self.tera {
+            Some(tera) if tera == *move_type => match (original, self.adaptability) {
+                (true, true) => 9216,
+                (true, false) | (false, true) => 8192,
+                (false, false) => 6144,
+            },
+            _ if original && self.adaptability && self.tera.is_none() => 8192,
+            _ if original => 6144,
+            _ => 4096,
+        }
+    }
+}
+
+/// Damage of one move, built up with the modifiers that apply.
+#[derive(Debug, Clone)]
+pub struct Damage<'a> {
+    attacker: &'a Pokemon,
+    defender: &'a Pokemon,
+    mv: &'a Move,
+    critical: bool,
+    spread: bool,
+    weather: Option<Weather>,
+    generation: Generation,
+    chart: Option<&'a Chart>,
+}
+
+impl<'a> Damage<'a> {
+    pub fn new(attacker: &'a Pokemon, defender: &'a Pokemon, mv: &'a Move) -> Self {
+        Self {
+            attacker,
+            defender,
+            mv,
+            critical: false,
+            spread: false,
+            weather: None,
+            generation: Generation::latest(),
+            chart: None,
+        }
+    }
+
+    pub fn critical(mut self) -> Self {
+        self.critical = true;
+        self
+    }
+
+    /// The move hits more than one target.
+    pub fn spread(mut self) -> Self {
+        self.spread = true;
+        self
+    }
+
+    pub fn weather(mut self, weather: Weather) -> Self {
+        self.weather = Some(weather);
+        self
+    }
+
+    pub fn generation(mut self, generation: Generation) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Matchups from `chart` instead of the generation's chart.
+    pub fn chart(mut self, chart: &'a Chart) -> Self {
+        self.chart = Some(chart);
+        self
+    }
+
+    pub fn effectiveness(&self) -> f64 {
+        let types = self.defender.defensive_types();
+        match self.chart {
+            Some(chart) => chart.combat_n(&self.mv.ty, &types),
+            None => Chart::generation(self.generation).combat_n(&self.mv.ty, &types),
+        }
+    }
+
+    pub fn calc(&self) -> DamageRange {
+        let hp = self.defender.stat(Stat::Hp);
+        let effectiveness = self.effectiveness();
+        if effectiveness == 0.0 || self.mv.power == 0 {
+            return DamageRange {
+                rolls: vec![0; 16],
+                hp,
+                effectiveness,
+            };
+        }
+
+        let base = self.base_damage();
+        let stab = self.attacker.stab(&self.mv.ty);
+        let burned = self.attacker.burned && self.mv.category == Category::Physical;
+        let rolls = (85..=100)
+            .map(|roll| {
+                let mut damage = base * roll / 100;
+                damage = poke_round(damage * stab);
+                damage = (damage as f64 * effectiveness) as u32;
+                if burned {
+                    damage /= 2;
+                }
+                damage.max(1)
+            })
+            .collect();
+
+        DamageRange {
+            rolls,
+            hp,
+            effectiveness,
+        }
+    }
+
+    /// Damage before the random roll, STAB, effectiveness and burn.
+    fn base_damage(&self) -> u32 {
+        let (attack, defense) = match self.mv.category {
+            Category::Physical => (Stat::Attack, Stat::Defense),
+            Category::Special => (Stat::SpAttack, Stat::SpDefense),
+        };
+        let attack = self.attacker.stat(attack);
+        let mut defense = self.defender.stat(defense);
+        let boost = match (self.weather, self.mv.category) {
+            (Some(Weather::Sandstorm), Category::Special) => Some(TypeRule::SandstormSpDefBoost),
+            (Some(Weather::Hail), Category::Physical) => Some(TypeRule::SnowDefBoost),
+            _ => None,
+        };
+        if let Some(boost) = boost {
+            if self
+                .defender
+                .defensive_types()
+                .iter()
+                .any(|ty| type_rules(ty, self.generation).contains(&boost))
+            {
+                defense = defense * 3 / 2;
+            }
+        }
+
+        let level = self.attacker.level;
+        let mut damage = (2 * level / 5 + 2) * self.mv.power * attack / defense.max(1) / 50 + 2;
+        if self.spread {
+            damage = poke_round(damage * 3072);
+        }
+        damage = match (self.weather, self.mv.ty) {
+            (Some(Weather::Sun), Type::Fire) | (Some(Weather::Rain), Type::Water) => {
+                poke_round(damage * 6144)
+            }
+            (Some(Weather::Sun), Type::Water) | (Some(Weather::Rain), Type::Fire) => {
+                poke_round(damage * 2048)
+            }
+            _ => damage,
+        };
+        if self.critical {
+            damage = if self.generation >= Generation::Gen6 {
+                damage * 3 / 2
+            } else {
+                damage * 2
+            };
+        }
+        damage
+    }
+}
+
+/// Rounds a value scaled by 4096, with halves rounding down.
+fn poke_round(scaled: u32) -> u32 {
+    let damage = scaled / 4096;
+    if scaled % 4096 > 2048 {
+        damage + 1
+    } else {
+        damage
+    }
+}
+
+/// The 16 equally likely damage rolls of a move against a defender.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamageRange {
+    pub rolls: Vec<u32>,
+    pub hp: u32,
+    pub effectiveness: f64,
+}
+
+impl DamageRange {
+    pub fn min(&self) -> u32 {
+        self.rolls.iter().copied().min().unwrap_or(0)
+    }
+
+    pub fn max(&self) -> u32 {
+        self.rolls.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn min_percent(&self) -> f64 {
+        self.percent(self.min())
+    }
+
+    pub fn max_percent(&self) -> f64 {
+        self.percent(self.max())
+    }
+
+    /// Probability that `hits` consecutive hits knock out the defender
+    /// from full HP.
+    pub fn ko_chance(&self, hits: u32) -> f64 {
+        // Damage totals capped at the defender's HP, with their number of
+        // roll combinations.
+        let mut totals: HashMap<u32, f64> = hashmap! { 0 => 1.0 };
+        for _ in 0..hits {
+            let mut next = HashMap::new();
+            for (total, count) in totals {
+                for roll in &self.rolls {
+                    *next.entry((total + roll).min(self.hp)).or_insert(0.0) += count;
+                }
+            }
+            totals = next;
+        }
+        let combinations = (self.rolls.len() as f64).powi(hits as i32);
+        totals.get(&self.hp).copied().unwrap_or(0.0) / combinations
+    }
+
+    fn percent(&self, damage: u32) -> f64 {
+        100.0 * damage as f64 / self.hp as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ruleset::Ruleset;
+    use crate::stats::Nature;
+    use crate::types::Type::*;
+
+    fn garchomp() -> Pokemon {
+        let spread = Spread::new([108, 130, 95, 80, 85, 102])
+            .evs([0, 252, 0, 0, 4, 252])
+            .nature(Nature::Jolly);
+        Pokemon::new(&[&Dragon, &Ground], &spread)
+    }
+
+    #[test]
+    fn earthquake() {
+        let attacker = garchomp();
+        let defender = garchomp();
+        let earthquake = Move::new(Ground, 100, Category::Physical);
+        let range = Damage::new(&attacker, &defender, &earthquake).calc();
+        assert_eq!(range.min(), 171);
+        assert_eq!(range.max(), 202);
+        assert_eq!(range.ko_chance(1), 0.0);
+        assert!(range.ko_chance(2) > 0.5 && range.ko_chance(2) < 1.0);
+        assert_eq!(range.ko_chance(3), 1.0);
+    }
+
+    #[test]
+    fn effectiveness_and_immunity() {
+        let attacker = garchomp();
+        let defender = garchomp();
+        let dragon_claw = Move::new(Dragon, 80, Category::Physical);
+        let damage = Damage::new(&attacker, &defender, &dragon_claw);
+        assert_eq!(damage.effectiveness(), 2.0);
+
+        let thunderbolt = Move::new(Electric, 90, Category::Special);
+        let range = Damage::new(&attacker, &defender, &thunderbolt).calc();
+        assert_eq!(range.max(), 0);
+        assert_eq!(range.ko_chance(3), 0.0);
+    }
+
+    #[test]
+    fn generation_chart() {
+        let attacker = garchomp();
+        let spread = Spread::new([75, 89, 105, 55, 125, 35]);
+        let defender = Pokemon::new(&[&Steel], &spread);
+        let shadow_ball = Move::new(Ghost, 80, Category::Special);
+        let damage = Damage::new(&attacker, &defender, &shadow_ball);
+        assert_eq!(damage.effectiveness(), 1.0);
+        let gen5 = damage.clone().generation(Generation::Gen5);
+        assert_eq!(gen5.effectiveness(), 0.5);
+        assert!(gen5.calc().max() < damage.calc().max());
+
+        let chart = Chart::builtin().with_ruleset(Ruleset::Go);
+        assert_eq!(damage.chart(&chart).effectiveness(), 1.0);
+        let flamethrower = Move::new(Fire, 90, Category::Special);
+        let fire = Damage::new(&attacker, &defender, &flamethrower).chart(&chart);
+        assert_eq!(fire.effectiveness(), 1.6);
+    }
+
+    #[test]
+    fn modifiers_reduce_damage() {
+        let attacker = garchomp();
+        let defender = garchomp();
+        let earthquake = Move::new(Ground, 100, Category::Physical);
+        let normal = Damage::new(&attacker, &defender, &earthquake).calc();
+        let spread = Damage::new(&attacker, &defender, &earthquake)
+            .spread()
+            .calc();
+        let burned = attacker.clone().burned();
+        let burn = Damage::new(&burned, &defender, &earthquake).calc();
+        let critical = Damage::new(&attacker, &defender, &earthquake)
+            .critical()
+            .calc();
+        assert!(spread.max() < normal.max());
+        assert!(burn.max() <= normal.max() / 2);
+        assert!(critical.min() > normal.max());
+    }
+
+    #[test]
+    fn stab() {
+        let attacker = garchomp();
+        assert_eq!(attacker.stab(&Ground), 6144);
+        assert_eq!(attacker.stab(&Fire), 4096);
+        assert_eq!(attacker.clone().adaptability().stab(&Ground), 8192);
+        assert_eq!(attacker.clone().tera(Ground).stab(&Ground), 8192);
+        assert_eq!(attacker.clone().tera(Fire).stab(&Fire), 6144);
+        assert_eq!(attacker.clone().tera(Fire).stab(&Dragon), 6144);
+        assert_eq!(
+            attacker.clone().tera(Ground).adaptability().stab(&Ground),
+            9216
+        );
+    }
+
+    #[test]
+    fn weather() {
+        let attacker = garchomp();
+        let defender = garchomp();
+        let flamethrower = Move::new(Fire, 90, Category::Special);
+        let normal = Damage::new(&attacker, &defender, &flamethrower).calc();
+        let sun = Damage::new(&attacker, &defender, &flamethrower)
+            .weather(Weather::Sun)
+            .calc();
+        let rain = Damage::new(&attacker, &defender, &flamethrower)
+            .weather(Weather::Rain)
+            .calc();
+        assert!(sun.max() > normal.max());
+        assert!(rain.max() < normal.max());
+    }
+}
@@ -0,0 +1,380 @@
+use crate::chart::Chart;
+use crate::showdown::json_string;
+use crate::suggest::{rank_typings, typings, Scorer};
+use crate::types::{ChartType, Type};
+use std::collections::HashMap;
+
+/// A matchup whose multiplier changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellChange<T: ChartType = Type> {
+    pub attack_type: T,
+    pub block_type: T,
+    pub old: f64,
+    pub new: f64,
+}
+
+/// A typing, present in both charts, that is weak to a different number
+/// of attacking types.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypingChange<T: ChartType = Type> {
+    pub types: Vec<T>,
+    pub old_weaknesses: usize,
+    pub new_weaknesses: usize,
+}
+
+/// Where a `list_types` team ranks in each chart; `None` if a chart lacks
+/// one of its types.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankShift<T: ChartType = Type> {
+    pub types: Vec<T>,
+    pub old_rank: Option<usize>,
+    pub new_rank: Option<usize>,
+}
+
+/// Everything that differs between two charts, and what it changes
+/// downstream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartDiff<T: ChartType = Type> {
+    pub added: Vec<T>,
+    pub removed: Vec<T>,
+    pub cells: Vec<CellChange<T>>,
+    pub typings: Vec<TypingChange<T>>,
+    /// Teams in the top `depth` of either chart whose rank changed.
+    pub rankings: Vec<RankShift<T>>,
+    /// Identifier and English name of every type of both charts.
+    labels: HashMap<T, (String, String)>,
+}
+
+impl<T: ChartType> ChartDiff<T> {
+    /// Compares `old` to `new`, following rank changes of their best
+    /// `depth` teams.
+    pub fn new(old: &Chart<T>, new: &Chart<T>, depth: usize) -> Self {
+        let added = new
+            .types()
+            .iter()
+            .filter(|ty| !old.has(ty))
+            .copied()
+            .collect();
+        let removed = old
+            .types()
+            .iter()
+            .filter(|ty| !new.has(ty))
+            .copied()
+            .collect();
+
+        let cells = old
+            .mismatches(new)
+            .into_iter()
+            .map(|(attack_type, block_type, old, new)| CellChange {
+                attack_type,
+                block_type,
+                old,
+                new,
+            })
+            .collect();
+
+        let weaknesses = |chart: &Chart<T>, types: &Vec<T>| {
+            chart
+                .types()
+                .iter()
+                .filter(|ty| chart.combat_n(*ty, types) > 1.0)
+                .count()
+        };
+        let typings = typings(old)
+            .into_iter()
+            .filter(|types| types.iter().all(|ty| new.has(ty)))
+            .filter_map(|types| {
+                let (old_weaknesses, new_weaknesses) =
+                    (weaknesses(old, &types), weaknesses(new, &types));
+                if old_weaknesses == new_weaknesses {
+                    return None;
+                }
+                Some(TypingChange {
+                    types,
+                    old_weaknesses,
+                    new_weaknesses,
+                })
+            })
+            .collect();
+
+        let ranks = |chart: &Chart<T>| -> HashMap<Vec<T>, usize> {
+            rank_typings(chart, chart.types().len().min(3), Scorer::LogRates)
+                .into_iter()
+                .enumerate()
+                .map(|(i, (types, _))| (sorted(types), i + 1))
+                .collect()
+        };
+        let (old_ranks, new_ranks) = (ranks(old), ranks(new));
+        let mut rankings: Vec<_> = old_ranks
+            .iter()
+            .chain(&new_ranks)
+            .filter(|(_, rank)| **rank <= depth)
+            .map(|(types, _)| types.clone())
+            .collect();
+        rankings.sort_by_key(|types| types.iter().map(ChartType::index).collect::<Vec<_>>());
+        rankings.dedup();
+        let mut rankings: Vec<_> = rankings
+            .into_iter()
+            .map(|types| RankShift {
+                old_rank: old_ranks.get(&types).copied(),
+                new_rank: new_ranks.get(&types).copied(),
+                types,
+            })
+            .filter(|shift| shift.old_rank != shift.new_rank)
+            .collect();
+        rankings.sort_by_key(|shift| shift.new_rank.unwrap_or(usize::MAX));
+
+        let labels = old
+            .types()
+            .iter()
+            .map(|ty| (ty, old))
+            .chain(new.types().iter().map(|ty| (ty, new)))
+            .map(|(ty, chart)| (*ty, (chart.identifier(ty), chart.name(ty, "en"))))
+            .collect();
+
+        Self {
+            added,
+            removed,
+            cells,
+            typings,
+            rankings,
+            labels,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.cells.is_empty()
+    }
+
+    fn name(&self, ty: &T) -> &str {
+        &self.labels[ty].1
+    }
+
+    fn id(&self, ty: &T) -> &str {
+        &self.labels[ty].0
+    }
+
+    fn names(&self, types: &[T]) -> String {
+        types
+            .iter()
+            .map(|ty| self.name(ty))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for ty in &self.added {
+            out += &format!("added: {}\n", self.name(ty));
+        }
+        for ty in &self.removed {
+            out += &format!("removed: {}\n", self.name(ty));
+        }
+        for cell in &self.cells {
+            out += &format!(
+                "{} -> {}: {} -> {}\n",
+                self.name(&cell.attack_type),
+                self.name(&cell.block_type),
+                cell.old,
+                cell.new
+            );
+        }
+        if !self.typings.is_empty() {
+            out += "weaknesses:\n";
+            for typing in &self.typings {
+                out += &format!(
+                    "    {}: {} -> {}\n",
+                    self.names(&typing.types),
+                    typing.old_weaknesses,
+                    typing.new_weaknesses
+                );
+            }
+        }
+        if !self.rankings.is_empty() {
+            out += "rankings:\n";
+            for shift in &self.rankings {
+                out += &format!(
+                    "    {}: {} -> {}\n",
+                    self.names(&shift.types),
+                    rank_str(shift.old_rank),
+                    rank_str(shift.new_rank)
+                );
+            }
+        }
+        out
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("## Type chart changes\n\n");
+        if !self.added.is_empty() || !self.removed.is_empty() {
+            for ty in &self.added {
+                out += &format!("- Added **{}**\n", self.name(ty));
+            }
+            for ty in &self.removed {
+                out += &format!("- Removed **{}**\n", self.name(ty));
+            }
+            out += "\n";
+        }
+        if self.cells.is_empty() {
+            out += "No matchup changed.\n";
+        } else {
+            out += "| Attacker | Defender | Old | New |\n|---|---|---:|---:|\n";
+            for cell in &self.cells {
+                out += &format!(
+                    "| {} | {} | {}× | {}× |\n",
+                    self.name(&cell.attack_type),
+                    self.name(&cell.block_type),
+                    cell.old,
+                    cell.new
+                );
+            }
+        }
+        if !self.typings.is_empty() {
+            out += "\n### Weaknesses\n\n| Typing | Old | New |\n|---|---:|---:|\n";
+            for typing in &self.typings {
+                out += &format!(
+                    "| {} | {} | {} |\n",
+                    self.names(&typing.types),
+                    typing.old_weaknesses,
+                    typing.new_weaknesses
+                );
+            }
+        }
+        if !self.rankings.is_empty() {
+            out += "\n### Team rankings\n\n| Team | Old | New |\n|---|---:|---:|\n";
+            for shift in &self.rankings {
+                out += &format!(
+                    "| {} | {} | {} |\n",
+                    self.names(&shift.types),
+                    rank_str(shift.old_rank),
+                    rank_str(shift.new_rank)
+                );
+            }
+        }
+        out
+    }
+
+    /// JSON with types by identifier and missing ranks as `null`.
+    pub fn to_json(&self) -> String {
+        let ids = |types: &[T]| {
+            let ids: Vec<_> = types.iter().map(|ty| json_string(self.id(ty))).collect();
+            format!("[{}]", ids.join(","))
+        };
+        let rank = |rank: Option<usize>| rank.map_or("null".to_string(), |r| r.to_string());
+        let cells: Vec<_> = self
+            .cells
+            .iter()
+            .map(|cell| {
+                format!(
+                    "{{\"attack\":{},\"defend\":{},\"old\":{},\"new\":{}}}",
+                    json_string(self.id(&cell.attack_type)),
+                    json_string(self.id(&cell.block_type)),
+                    cell.old,
+                    cell.new
+                )
+            })
+            .collect();
+        let typings: Vec<_> = self
+            .typings
+            .iter()
+            .map(|typing| {
+                format!(
+                    "{{\"types\":{},\"old_weaknesses\":{},\"new_weaknesses\":{}}}",
+                    ids(&typing.types),
+                    typing.old_weaknesses,
+                    typing.new_weaknesses
+                )
+            })
+            .collect();
+        let rankings: Vec<_> = self
+            .rankings
+            .iter()
+            .map(|shift| {
+                format!(
+                    "{{\"types\":{},\"old_rank\":{},\"new_rank\":{}}}",
+                    ids(&shift.types),
+                    rank(shift.old_rank),
+                    rank(shift.new_rank)
+                )
+            })
+            .collect();
+        format!(
+            "{{\"added\":{},\"removed\":{},\"cells\":[{}],\"typings\":[{}],\"rankings\":[{}]}}\n",
+            ids(&self.added),
+            ids(&self.removed),
+            cells.join(","),
+            typings.join(","),
+            rankings.join(",")
+        )
+    }
+}
+
+fn sorted<T: ChartType>(mut types: Vec<T>) -> Vec<T> {
+    types.sort_by_key(ChartType::index);
+    types
+}
+
+fn rank_str(rank: Option<usize>) -> String {
+    rank.map_or("-".to_string(), |r| r.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::Generation;
+    use crate::types::Type::*;
+
+    #[test]
+    fn gen5_to_gen6() {
+        let diff = ChartDiff::new(&Chart::generation(Generation::Gen5), &Chart::builtin(), 10);
+        assert_eq!(diff.added, vec![Fairy]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(
+            diff.cells,
+            vec![
+                CellChange {
+                    attack_type: Ghost,
+                    block_type: Steel,
+                    old: 0.5,
+                    new: 1.0
+                },
+                CellChange {
+                    attack_type: Dark,
+                    block_type: Steel,
+                    old: 0.5,
+                    new: 1.0
+                },
+            ]
+        );
+        // Fairy hits Dragon, Fight and Dark.
+        let dragon = diff
+            .typings
+            .iter()
+            .find(|t| t.types == vec![Dragon])
+            .unwrap();
+        assert_eq!((dragon.old_weaknesses, dragon.new_weaknesses), (2, 3));
+        assert!(diff
+            .rankings
+            .iter()
+            .any(|shift| shift.types.contains(&Fairy) && shift.old_rank.is_none()));
+    }
+
+    #[test]
+    fn formats() {
+        let mut new = Chart::builtin();
+        new.set(&Fire, &Water, 2.0);
+        let diff = ChartDiff::new(&Chart::builtin(), &new, 5);
+        assert!(diff.to_text().starts_with("Fire -> Water: 0.5 -> 2\n"));
+        assert!(diff.to_markdown().contains("| Fire | Water | 0.5× | 2× |"));
+        assert!(diff.to_json().contains(
+            "\"cells\":[{\"attack\":\"fire\",\"defend\":\"water\",\"old\":0.5,\"new\":2}]"
+        ));
+        assert!(ChartDiff::new(&new, &new, 5).is_empty());
+
+        let old = Chart::from_builtin(&Chart::neutral(&[Fire]));
+        let mut new = old.clone();
+        new.add_type("a\"b\\");
+        let json = ChartDiff::new(&old, &new, 5).to_json();
+        assert!(json.starts_with("{\"added\":[\"a\\\"b\\\\\"],"));
+    }
+}
@@ -0,0 +1,275 @@
+use crate::chart::Chart;
+use crate::ruleset::Stacking;
+use crate::types::{BlockTypes, Type};
+use std::collections::HashMap;
+
+/// Battle conditions that change how a defender's types are matched up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Condition {
+    Gravity,
+    IronBall,
+    SmackDown,
+    ThousandArrows,
+    Roost,
+    RingTarget,
+    StrongWinds,
+}
+
+impl Condition {
+    pub fn conditions() -> [Condition; 7] {
+        use Condition::*;
+        [
+            Gravity,
+            IronBall,
+            SmackDown,
+            ThousandArrows,
+            Roost,
+            RingTarget,
+            StrongWinds,
+        ]
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Condition> {
+        Condition::conditions()
+            .iter()
+            .find(|condition| condition.to_str() == s)
+            .copied()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        use Condition::*;
+        match *self {
+            Gravity => "Gravity",
+            IronBall => "IronBall",
+            SmackDown => "SmackDown",
+            ThousandArrows => "ThousandArrows",
+            Roost => "Roost",
+            RingTarget => "RingTarget",
+            StrongWinds => "StrongWinds",
+        }
+    }
+
+    /// Whether the condition pulls a Flying defender to the ground.
+    pub fn grounds(&self) -> bool {
+        use Condition::*;
+        matches!(*self, Gravity | IronBall | SmackDown | ThousandArrows)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Weather {
+    Sun,
+    Rain,
+    Sandstorm,
+    /// Hail, or snow from Gen 9 onwards.
+    Hail,
+}
+
+impl Weather {
+    pub fn weathers() -> [Weather; 4] {
+        use Weather::*;
+        [Sun, Rain, Sandstorm, Hail]
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Weather> {
+        Weather::weathers()
+            .iter()
+            .find(|weather| weather.to_str().eq_ignore_ascii_case(s))
+            .copied()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        use Weather::*;
+        match *self {
+            Sun => "Sun",
+            Rain => "Rain",
+            Sandstorm => "Sandstorm",
+            Hail => "Hail",
+        }
+    }
+}
+
+/// A set of active conditions applied to the defending side before
+/// effectiveness is computed.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BattleField {
+    conditions: Vec<Condition>,
+}
+
+impl BattleField {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, condition: Condition) -> Self {
+        if !self.has(condition) {
+            self.conditions.push(condition);
+        }
+        self
+    }
+
+    pub fn has(&self, condition: Condition) -> bool {
+        self.conditions.contains(&condition)
+    }
+
+    pub fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.conditions.iter().any(Condition::grounds)
+    }
+
+    /// Defending types after type-changing conditions. Roost drops the
+    /// Flying type for the turn; a pure Flying type becomes Normal.
+    pub fn block_types<'a, B: BlockTypes + ?Sized>(&self, block_types: &'a B) -> Vec<&'a Type> {
+        let block_types = block_types.block_types();
+        if !self.has(Condition::Roost) || !block_types.contains(&&Type::Flying) {
+            return block_types;
+        }
+
+        let types: Vec<_> = block_types
+            .into_iter()
+            .filter(|ty| **ty != Type::Flying)
+            .collect();
+        if types.is_empty() {
+            vec![&Type::Normal]
+        } else {
+            types
+        }
+    }
+
+    /// The mainline multiplier of a matchup in `chart` on this field.
+    pub fn relation(&self, chart: &Chart, attack_type: &Type, block_type: &Type) -> f64 {
+        let rate = chart.relation(attack_type, block_type);
+
+        if *block_type == Type::Flying {
+            if *attack_type == Type::Ground && self.is_grounded() {
+                return 1.0;
+            }
+            if rate > 1.0 && self.has(Condition::StrongWinds) {
+                return 1.0;
+            }
+        }
+        if rate == 0.0 && self.has(Condition::RingTarget) {
+            return 1.0;
+        }
+        rate
+    }
+
+    pub fn combat1(&self, chart: &Chart, attack_type: &Type, block_type: &Type) -> f64 {
+        chart
+            .ruleset()
+            .multiplier(self.relation(chart, attack_type, block_type))
+    }
+
+    pub fn combat_n<B: BlockTypes + ?Sized>(
+        &self,
+        chart: &Chart,
+        attack_type: &Type,
+        block_types: &B,
+    ) -> f64 {
+        let relations: Vec<_> = self
+            .block_types(block_types)
+            .iter()
+            .map(|block_type| self.relation(chart, attack_type, block_type))
+            .collect();
+        chart.ruleset().stack(&relations)
+    }
+
+    pub fn blocks_rates<B: BlockTypes + ?Sized>(
+        &self,
+        chart: &Chart,
+        block_types: &B,
+    ) -> HashMap<Type, f64> {
+        chart
+            .types()
+            .iter()
+            .map(|attack_type| (*attack_type, self.combat_n(chart, attack_type, block_types)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::Generation;
+    use crate::ruleset::Ruleset;
+
+    #[test]
+    fn empty_field_matches_type_chart() {
+        use crate::types::Type::*;
+        let field = BattleField::new();
+        for ruleset in Ruleset::rulesets().iter() {
+            let chart = Chart::generation(Generation::Gen1).with_ruleset(ruleset.clone());
+            assert_eq!(
+                field.blocks_rates(&chart, &[&Water, &Ground]),
+                chart.blocks_rates(&[&Water, &Ground])
+            );
+        }
+    }
+
+    #[test]
+    fn grounding() {
+        use crate::types::Type::*;
+        let chart = Chart::builtin();
+        assert_eq!(chart.combat_n(&Ground, &[&Flying, &Steel]), 0.0);
+        for condition in Condition::conditions().iter().filter(|c| c.grounds()) {
+            let field = BattleField::new().with(*condition);
+            assert_eq!(field.combat_n(&chart, &Ground, &[&Flying, &Steel]), 2.0);
+            assert_eq!(field.combat_n(&chart, &Electric, &[&Flying, &Steel]), 2.0);
+        }
+    }
+
+    #[test]
+    fn follows_chart_ruleset() {
+        use crate::types::Type::*;
+        let field = BattleField::new().with(Condition::Gravity);
+        let go = Chart::builtin().with_ruleset(Ruleset::Go);
+        assert_eq!(go.combat_n(&Ground, &[&Flying, &Steel]), 0.625);
+        assert_eq!(field.combat_n(&go, &Ground, &[&Flying, &Steel]), 1.6);
+        assert_eq!(field.combat1(&go, &Ground, &Flying), 1.0);
+    }
+
+    #[test]
+    fn roost() {
+        use crate::types::Type::*;
+        let chart = Chart::builtin();
+        let field = BattleField::new().with(Condition::Roost);
+        assert_eq!(field.block_types(&[&Flying, &Steel]), vec![&Steel]);
+        assert_eq!(field.block_types(&[&Flying]), vec![&Normal]);
+        assert_eq!(field.combat_n(&chart, &Ground, &[&Flying, &Fire]), 2.0);
+        assert_eq!(field.combat_n(&chart, &Fight, &[&Flying]), 2.0);
+    }
+
+    #[test]
+    fn ring_target() {
+        use crate::types::Type::*;
+        let chart = Chart::builtin();
+        let field = BattleField::new().with(Condition::RingTarget);
+        assert_eq!(field.combat_n(&chart, &Normal, &[&Ghost]), 1.0);
+        assert_eq!(field.combat_n(&chart, &Fight, &[&Ghost, &Steel]), 2.0);
+        assert_eq!(field.combat_n(&chart, &Ground, &[&Flying, &Electric]), 2.0);
+    }
+
+    #[test]
+    fn strong_winds() {
+        use crate::types::Type::*;
+        let chart = Chart::builtin();
+        let field = BattleField::new().with(Condition::StrongWinds);
+        assert_eq!(field.combat_n(&chart, &Rock, &[&Flying, &Dragon]), 1.0);
+        assert_eq!(field.combat_n(&chart, &Ice, &[&Flying, &Dragon]), 2.0);
+        assert_eq!(field.combat_n(&chart, &Rock, &[&Flying, &Bug]), 2.0);
+        assert_eq!(field.combat_n(&chart, &Ground, &[&Flying]), 0.0);
+    }
+
+    #[test]
+    fn condition_from_str() {
+        for condition in Condition::conditions().iter() {
+            assert_eq!(Condition::from_str(condition.to_str()), Some(*condition));
+        }
+        assert_eq!(Condition::from_str("Sunny"), None);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::chart::Chart;
+use crate::suggest::typings;
+use crate::types::{ChartType, Type};
+
+/// Optimal mixed strategies of a zero-sum game, as probabilities for the
+/// row (maximizing) and column (minimizing) player, and the game's value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    pub rows: Vec<f64>,
+    pub columns: Vec<f64>,
+    pub value: f64,
+}
+
+/// Solves the zero-sum game with the given payoffs to the row player by
+/// linear programming.
+pub fn solve_zero_sum(payoffs: &[Vec<f64>]) -> Solution {
+    let rows = payoffs.len();
+    let columns = payoffs.first().map_or(0, Vec::len);
+    if rows == 0 || columns == 0 {
+        return Solution {
+            rows: vec![1.0; rows.min(1)],
+            columns: vec![1.0; columns.min(1)],
+            value: 0.0,
+        };
+    }
+
+    // Shift payoffs so they are all positive; the value shifts with them.
+    let min = payoffs
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::INFINITY, f64::min);
+    let shift = 1.0 - min;
+
+    // The column player's LP: maximize sum(y) subject to M y <= 1, y >= 0.
+    // The tableau has `rows` constraint rows plus the objective row, and
+    // `columns` variables, `rows` slacks and the right hand side.
+    let width = columns + rows + 1;
+    let mut tableau = vec![vec![0.0; width]; rows + 1];
+    for (i, row) in payoffs.iter().enumerate() {
+        for (j, payoff) in row.iter().enumerate() {
+            tableau[i][j] = payoff + shift;
+        }
+        tableau[i][columns + i] = 1.0;
+        tableau[i][width - 1] = 1.0;
+    }
+    for cell in tableau[rows][..columns].iter_mut() {
+        *cell = -1.0;
+    }
+    let mut basis: Vec<usize> = (columns..columns + rows).collect();
+
+    const EPSILON: f64 = 1e-12;
+    // Bland's rule: lowest index entering and leaving variables, so the
+    // method cannot cycle.
+    while let Some(entering) = (0..width - 1).find(|j| tableau[rows][*j] < -EPSILON) {
+        let leaving = (0..rows)
+            .filter(|i| tableau[*i][entering] > EPSILON)
+            .min_by(|a, b| {
+                let ratio = |i: usize| tableau[i][width - 1] / tableau[i][entering];
+                ratio(*a)
+                    .partial_cmp(&ratio(*b))
+                    .unwrap()
+                    .then(basis[*a].cmp(&basis[*b]))
+            })
+            .expect("the game LP is bounded");
+
+        let pivot = tableau[leaving][entering];
+        for cell in tableau[leaving].iter_mut() {
+            *cell /= pivot;
+        }
+        let pivot_row = tableau[leaving].clone();
+        for (i, row) in tableau.iter_mut().enumerate() {
+            let factor = row[entering];
+            if i != leaving && factor != 0.0 {
+                for (cell, pivot_cell) in row.iter_mut().zip(&pivot_row) {
+                    *cell -= factor * pivot_cell;
+                }
+            }
+        }
+        basis[leaving] = entering;
+    }
+
+    let total = tableau[rows][width - 1];
+    let value = 1.0 / total;
+    let mut column_strategy = vec![0.0; columns];
+    for (i, var) in basis.iter().enumerate() {
+        if *var < columns {
+            column_strategy[*var] = tableau[i][width - 1] * value;
+        }
+    }
+    // The row player's strategy is the dual solution, found in the
+    // objective row under the slack variables.
+    let row_strategy = (0..rows)
+        .map(|i| tableau[rows][columns + i] * value)
+        .collect();
+    Solution {
+        rows: row_strategy,
+        columns: column_strategy,
+        value: value - shift,
+    }
+}
+
+/// The type chart as a zero-sum game: the attacker picks an attacking
+/// type, the defender a typing, and the attacker wins the log2 of the
+/// multiplier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeGame {
+    dual: bool,
+    immune: f64,
+}
+
+impl Default for TypeGame {
+    fn default() -> Self {
+        TypeGame::new()
+    }
+}
+
+impl TypeGame {
+    /// The mono-type game, with immunities worth -3 (an eighth).
+    pub fn new() -> Self {
+        Self {
+            dual: false,
+            immune: -3.0,
+        }
+    }
+
+    /// Lets the defender pick dual typings as well.
+    pub fn dual(mut self) -> Self {
+        self.dual = true;
+        self
+    }
+
+    /// Payoff of an immunity, since log2(0) is not finite.
+    pub fn immune(mut self, payoff: f64) -> Self {
+        self.immune = payoff;
+        self
+    }
+
+    pub fn payoff(&self, rate: f64) -> f64 {
+        if rate == 0.0 {
+            self.immune
+        } else {
+            rate.log2()
+        }
+    }
+
+    pub fn solve<T: ChartType>(&self, chart: &Chart<T>) -> Equilibrium<T> {
+        let defenders: Vec<Vec<T>> = if self.dual {
+            typings(chart)
+        } else {
+            chart.types().iter().map(|ty| vec![*ty]).collect()
+        };
+        let payoffs: Vec<Vec<f64>> = chart
+            .types()
+            .iter()
+            .map(|attack_type| {
+                defenders
+                    .iter()
+                    .map(|types| self.payoff(chart.combat_n(attack_type, types)))
+                    .collect()
+            })
+            .collect();
+        let solution = solve_zero_sum(&payoffs);
+
+        let mut attack: Vec<_> = chart
+            .types()
+            .iter()
+            .copied()
+            .zip(solution.rows)
+            .filter(|(_, p)| *p > 1e-9)
+            .collect();
+        attack.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap());
+        let mut defense: Vec<_> = defenders
+            .into_iter()
+            .zip(solution.columns)
+            .filter(|(_, p)| *p > 1e-9)
+            .collect();
+        defense.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap());
+        Equilibrium {
+            attack,
+            defense,
+            value: solution.value,
+        }
+    }
+}
+
+/// Equilibrium of the type game: the types each side plays with nonzero
+/// probability, most likely first, and the attacker's expected log2
+/// multiplier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equilibrium<T: ChartType = Type> {
+    pub attack: Vec<(T, f64)>,
+    pub defense: Vec<(Vec<T>, f64)>,
+    pub value: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    fn assert_equilibrium(payoffs: &[Vec<f64>], solution: &Solution) {
+        assert_close(solution.rows.iter().sum(), 1.0);
+        assert_close(solution.columns.iter().sum(), 1.0);
+        for j in 0..payoffs[0].len() {
+            let row_payoff: f64 = (0..payoffs.len())
+                .map(|i| solution.rows[i] * payoffs[i][j])
+                .sum();
+            assert!(row_payoff >= solution.value - 1e-9);
+        }
+        for row in payoffs {
+            let column_payoff: f64 = row.iter().zip(&solution.columns).map(|(a, q)| a * q).sum();
+            assert!(column_payoff <= solution.value + 1e-9);
+        }
+    }
+
+    #[test]
+    fn rock_paper_scissors() {
+        let payoffs = vec![
+            vec![0.0, -1.0, 1.0],
+            vec![1.0, 0.0, -1.0],
+            vec![-1.0, 1.0, 0.0],
+        ];
+        let solution = solve_zero_sum(&payoffs);
+        assert_close(solution.value, 0.0);
+        for p in solution.rows.iter().chain(&solution.columns) {
+            assert_close(*p, 1.0 / 3.0);
+        }
+    }
+
+    #[test]
+    fn saddle_point() {
+        let payoffs = vec![vec![3.0, 5.0], vec![1.0, 2.0]];
+        let solution = solve_zero_sum(&payoffs);
+        assert_close(solution.value, 3.0);
+        assert_equilibrium(&payoffs, &solution);
+    }
+
+    #[test]
+    fn type_games() {
+        let chart = Chart::builtin();
+        for game in [TypeGame::new(), TypeGame::new().dual()].iter() {
+            let equilibrium = game.solve(&chart);
+            assert_close(equilibrium.attack.iter().map(|(_, p)| p).sum(), 1.0);
+            assert_close(equilibrium.defense.iter().map(|(_, p)| p).sum(), 1.0);
+        }
+        let mono = TypeGame::new().solve(&chart);
+        let dual = TypeGame::new().dual().solve(&chart);
+        assert!(dual.value < mono.value);
+
+        let payoffs: Vec<Vec<f64>> = chart
+            .types()
+            .iter()
+            .map(|a| {
+                chart
+                    .types()
+                    .iter()
+                    .map(|b| TypeGame::new().payoff(chart.combat1(a, b)))
+                    .collect()
+            })
+            .collect();
+        assert_equilibrium(&payoffs, &solve_zero_sum(&payoffs));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::types::Type;
+
+/// Main series generations, used to select rules that changed over time.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Generation {
+    Gen1 = 1,
+    Gen2,
+    Gen3,
+    Gen4,
+    Gen5,
+    Gen6,
+    Gen7,
+    Gen8,
+    Gen9,
+}
+
+impl Generation {
+    pub fn generations() -> [Generation; 9] {
+        use Generation::*;
+        [Gen1, Gen2, Gen3, Gen4, Gen5, Gen6, Gen7, Gen8, Gen9]
+    }
+
+    pub fn latest() -> Generation {
+        Generation::Gen9
+    }
+
+    pub fn number(&self) -> u8 {
+        *self as u8
+    }
+
+    pub fn from_number(n: u8) -> Option<Generation> {
+        Generation::generations()
+            .iter()
+            .find(|gen| gen.number() == n)
+            .copied()
+    }
+
+    /// Types that exist in this generation.
+    pub fn types(&self) -> Vec<Type> {
+        Type::types()
+            .iter()
+            .filter(|ty| Generation::introduced(ty) <= *self)
+            .copied()
+            .collect()
+    }
+
+    pub fn introduced(ty: &Type) -> Generation {
+        match *ty {
+            Type::Dark | Type::Steel => Generation::Gen2,
+            Type::Fairy => Generation::Gen6,
+            _ => Generation::Gen1,
+        }
+    }
+}
+
+impl Default for Generation {
+    fn default() -> Self {
+        Generation::latest()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn types_per_generation() {
+        assert_eq!(Generation::Gen1.types().len(), 15);
+        assert_eq!(Generation::Gen5.types().len(), 17);
+        assert_eq!(Generation::Gen6.types().len(), 18);
+    }
+
+    #[test]
+    fn from_number() {
+        assert_eq!(Generation::from_number(4), Some(Generation::Gen4));
+        assert_eq!(Generation::from_number(0), None);
+        assert_eq!(Generation::from_number(10), None);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::balance::{dominated, symmetry};
+use crate::chart::Chart;
+use crate::graph::EdgeKind;
+use crate::types::{CustomType, Type};
+
+/// Generates random type charts that pass balance constraints, for fan
+/// games and randomizers. The same settings and seed always give the same
+/// chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartGenerator {
+    size: usize,
+    seed: u64,
+    super_effective: f64,
+    resisted: f64,
+    immunities: usize,
+    symmetry: Option<f64>,
+    attempts: usize,
+}
+
+impl ChartGenerator {
+    /// A chart of `size` types, the built-in ones first and then custom
+    /// types named "type-19" and so on.
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            seed: 0,
+            super_effective: 0.15,
+            resisted: 0.2,
+            immunities: 8,
+            symmetry: None,
+            attempts: 10_000,
+        }
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Chances of a matchup being super effective or resisted; the
+    /// built-in chart has about 0.15 and 0.2.
+    pub fn density(mut self, super_effective: f64, resisted: f64) -> Self {
+        self.super_effective = super_effective;
+        self.resisted = resisted;
+        self
+    }
+
+    /// Exact number of immunities.
+    pub fn immunities(mut self, n: usize) -> Self {
+        self.immunities = n;
+        self
+    }
+
+    /// Mirrors matchups so `Balance::symmetry` is at least `min`: a super
+    /// effective hit tends to be resisted in return.
+    pub fn symmetry(mut self, min: f64) -> Self {
+        self.symmetry = Some(min);
+        self
+    }
+
+    /// Candidates to try before giving up.
+    pub fn attempts(mut self, n: usize) -> Self {
+        self.attempts = n;
+        self
+    }
+
+    /// The first candidate where every type has a weakness and a
+    /// resistance or immunity, and no type is dominated.
+    pub fn generate(&self) -> Option<Chart<CustomType>> {
+        let mut rng = SplitMix64(self.seed);
+        (0..self.attempts)
+            .map(|_| self.candidate(&mut rng))
+            .find(|chart| self.accepts(chart))
+    }
+
+    fn candidate(&self, rng: &mut SplitMix64) -> Chart<CustomType> {
+        let builtin: Vec<_> = Type::types()
+            .iter()
+            .take(self.size)
+            .map(CustomType::from)
+            .collect();
+        let mut chart = Chart::neutral(&builtin);
+        for i in builtin.len()..self.size {
+            chart.add_type(&format!("type-{}", i + 1));
+        }
+        let types = chart.types().to_vec();
+
+        for a in &types {
+            for b in &types {
+                let roll = rng.next_f64();
+                let rate = if roll < self.super_effective {
+                    2.0
+                } else if roll < self.super_effective + self.resisted {
+                    0.5
+                } else {
+                    1.0
+                };
+                chart.set(a, b, rate);
+            }
+        }
+        if let Some(min) = self.symmetry {
+            for (i, a) in types.iter().enumerate() {
+                for b in &types[i + 1..] {
+                    if rng.next_f64() < min {
+                        let mirror = match chart.relation(a, b) {
+                            rate if rate > 1.0 => 0.5,
+                            rate if rate < 1.0 => 2.0,
+                            _ => 1.0,
+                        };
+                        chart.set(b, a, mirror);
+                    }
+                }
+            }
+        }
+
+        let cells = types.len() * types.len();
+        let mut placed = 0;
+        while placed < self.immunities.min(cells) {
+            let cell = rng.below(cells);
+            let (a, b) = (&types[cell / types.len()], &types[cell % types.len()]);
+            if chart.relation(a, b) != 0.0 {
+                chart.set(a, b, 0.0);
+                placed += 1;
+            }
+        }
+        chart
+    }
+
+    fn accepts(&self, chart: &Chart<CustomType>) -> bool {
+        let types = chart.types();
+        let balanced = types.iter().all(|ty| {
+            let taken: Vec<_> = types
+                .iter()
+                .filter_map(|a| EdgeKind::of(chart.combat1(a, ty)))
+                .collect();
+            taken.contains(&EdgeKind::SuperEffective)
+                && taken.iter().any(|kind| *kind != EdgeKind::SuperEffective)
+        });
+        balanced
+            && self.symmetry.is_none_or(|min| symmetry(chart) >= min)
+            && dominated(chart).is_empty()
+    }
+}
+
+/// SplitMix64, so charts stay reproducible without depending on a random
+/// number crate's algorithm.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ChartType;
+
+    fn immunities(chart: &Chart<CustomType>) -> usize {
+        let types = chart.types();
+        types
+            .iter()
+            .flat_map(|a| types.iter().map(move |b| (a, b)))
+            .filter(|(a, b)| chart.relation(a, b) == 0.0)
+            .count()
+    }
+
+    #[test]
+    fn reproducible() {
+        let generator = ChartGenerator::new(18).seed(42);
+        let chart = generator.generate().unwrap();
+        assert_eq!(generator.generate().unwrap(), chart);
+        assert_ne!(ChartGenerator::new(18).seed(43).generate().unwrap(), chart);
+        assert_eq!(immunities(&chart), 8);
+        assert!(dominated(&chart).is_empty());
+        assert!(chart.types().iter().all(|ty| ty.builtin().is_some()));
+    }
+
+    #[test]
+    fn constraints() {
+        let chart = ChartGenerator::new(20)
+            .seed(7)
+            .immunities(3)
+            .symmetry(0.8)
+            .generate()
+            .unwrap();
+        assert_eq!(chart.types().len(), 20);
+        assert_eq!(chart.identifier(&chart.types()[19]), "type-20");
+        assert_eq!(immunities(&chart), 3);
+        assert!(symmetry(&chart) >= 0.8);
+    }
+}
@@ -0,0 +1,129 @@
+//! Golden-file tests for the checked-in `list_types` rankings. After an
+//! intended change to the chart or the scoring, rewrite the files with
+//! `BLESS=1 cargo test golden`.
+
+use crate::chart::Chart;
+use crate::suggest::{format_ranking, rank_typings, Scorer};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Differing lines shown before the rest are only counted.
+const SHOWN_LINES: usize = 10;
+
+/// Whether two ranking lines agree. Scores may differ in the last bits, as
+/// `log10` is not correctly rounded on every platform.
+fn lines_match(expected: &str, actual: &str) -> bool {
+    match (expected.split_once(" = "), actual.split_once(" = ")) {
+        _ if expected == actual => true,
+        (Some((types_e, score_e)), Some((types_a, score_a))) if types_e == types_a => {
+            match (score_e.parse::<f64>(), score_a.parse::<f64>()) {
+                (Ok(e), Ok(a)) => (e - a).abs() <= e.abs().max(a.abs()) * 1e-15,
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// A readable report of where `actual` departs from `expected`, or `None`
+/// if they match.
+fn compare(expected: &str, actual: &str) -> Option<String> {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+    let differing: Vec<_> = (0..expected.len().max(actual.len()))
+        .filter(|i| match (expected.get(*i), actual.get(*i)) {
+            (Some(e), Some(a)) => !lines_match(e, a),
+            _ => true,
+        })
+        .collect();
+    if differing.is_empty() {
+        return None;
+    }
+
+    let mut report = format!(
+        "{} of {} lines differ (expected {} lines, got {})\n",
+        differing.len(),
+        expected.len().max(actual.len()),
+        expected.len(),
+        actual.len()
+    );
+    for i in differing.iter().take(SHOWN_LINES) {
+        report += &format!("line {}:\n", i + 1);
+        if let Some(line) = expected.get(*i) {
+            report += &format!("  - {}\n", line);
+        }
+        if let Some(line) = actual.get(*i) {
+            report += &format!("  + {}\n", line);
+        }
+    }
+    if differing.len() > SHOWN_LINES {
+        report += &format!("... and {} more\n", differing.len() - SHOWN_LINES);
+    }
+    Some(report)
+}
+
+/// Checks `actual` against a checked-in file, or rewrites the file in
+/// bless mode, keeping its line endings.
+fn check_golden(file: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(file);
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    if env::var_os("BLESS").is_some() {
+        let actual = if expected.contains("\r\n") {
+            actual.replace('\n', "\r\n")
+        } else {
+            actual.to_string()
+        };
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    if let Some(report) = compare(&expected.replace("\r\n", "\n"), actual) {
+        panic!(
+            "`{}` no longer matches the ranking; if the change is intended, \
+             run `BLESS=1 cargo test golden`\n{}",
+            file, report
+        );
+    }
+}
+
+fn check_ranking(file: &str, size: usize, scorer: Scorer) {
+    let ranking = rank_typings(&Chart::builtin(), size, scorer);
+    check_golden(file, &format_ranking(&ranking));
+}
+
+#[test]
+fn data() {
+    check_ranking("data", 3, Scorer::Multipliers);
+}
+
+#[test]
+fn data4() {
+    check_ranking("data4", 4, Scorer::Multipliers);
+}
+
+#[test]
+fn data5() {
+    check_ranking("data5", 5, Scorer::Multipliers);
+}
+
+#[test]
+fn more() {
+    check_ranking("more", 4, Scorer::LogMultipliers);
+}
+
+#[test]
+fn reports_differences() {
+    let expected = "types: 2\n[Fire] = 1\n[Water] = 2\n";
+    assert_eq!(compare(expected, expected), None);
+    assert_eq!(
+        compare(
+            expected,
+            "types: 2\n[Fire] = 1.0000000000000002\n[Water] = 2\n"
+        ),
+        None
+    );
+    let report = compare(expected, "types: 2\n[Water] = 2\n").unwrap();
+    assert!(report.starts_with("2 of 3 lines differ (expected 3 lines, got 2)\n"));
+    assert!(report.contains("line 2:\n  - [Fire] = 1\n  + [Water] = 2\n"));
+    assert!(report.contains("line 3:\n  - [Water] = 2\n"));
+}
@@ -0,0 +1,436 @@
+use crate::chart::Chart;
+use crate::types::{ChartType, Localization, Type};
+use std::f64::consts::PI;
+
+/// Kinds of non-neutral matchups.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    SuperEffective,
+    Resisted,
+    Immune,
+}
+
+impl EdgeKind {
+    pub fn kinds() -> [EdgeKind; 3] {
+        use EdgeKind::*;
+        [SuperEffective, Resisted, Immune]
+    }
+
+    pub fn of(rate: f64) -> Option<EdgeKind> {
+        if rate == 0.0 {
+            Some(EdgeKind::Immune)
+        } else if rate < 1.0 {
+            Some(EdgeKind::Resisted)
+        } else if rate > 1.0 {
+            Some(EdgeKind::SuperEffective)
+        } else {
+            None
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<EdgeKind> {
+        EdgeKind::kinds()
+            .iter()
+            .find(|kind| kind.to_str().eq_ignore_ascii_case(s))
+            .copied()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        use EdgeKind::*;
+        match *self {
+            SuperEffective => "super-effective",
+            Resisted => "resisted",
+            Immune => "immune",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        use EdgeKind::*;
+        match *self {
+            SuperEffective => "#2e7d32",
+            Resisted => "#c62828",
+            Immune => "#757575",
+        }
+    }
+}
+
+/// An edge from an attacking type to a defending type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge<T: ChartType = Type> {
+    pub from: T,
+    pub to: T,
+    pub kind: EdgeKind,
+    pub rate: f64,
+}
+
+/// In- and out-degree of one type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Degree<T: ChartType = Type> {
+    pub ty: T,
+    /// Edges into the type, i.e. attacking types it is on the receiving
+    /// end of.
+    pub in_degree: usize,
+    /// Edges out of the type, i.e. defending types it hits.
+    pub out_degree: usize,
+}
+
+/// The chart as a directed graph of non-neutral matchups.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeGraph<T: ChartType = Type> {
+    pub nodes: Vec<T>,
+    pub edges: Vec<Edge<T>>,
+    /// The source chart, for type names.
+    chart: Chart<T>,
+}
+
+impl<T: ChartType> TypeGraph<T> {
+    pub fn new(chart: &Chart<T>) -> Self {
+        let nodes = chart.types().to_vec();
+        let mut edges = Vec::new();
+        for from in &nodes {
+            for to in &nodes {
+                let rate = chart.combat1(from, to);
+                if let Some(kind) = EdgeKind::of(rate) {
+                    edges.push(Edge {
+                        from: *from,
+                        to: *to,
+                        kind,
+                        rate,
+                    });
+                }
+            }
+        }
+        Self {
+            nodes,
+            edges,
+            chart: chart.clone(),
+        }
+    }
+
+    /// The graph with only edges of the given kinds.
+    pub fn filter(&self, kinds: &[EdgeKind]) -> Self {
+        Self {
+            nodes: self.nodes.clone(),
+            edges: self
+                .edges
+                .iter()
+                .filter(|edge| kinds.contains(&edge.kind))
+                .cloned()
+                .collect(),
+            chart: self.chart.clone(),
+        }
+    }
+
+    pub fn degrees(&self) -> Vec<Degree<T>> {
+        self.nodes
+            .iter()
+            .map(|ty| Degree {
+                ty: *ty,
+                in_degree: self.edges.iter().filter(|e| e.to == *ty).count(),
+                out_degree: self.edges.iter().filter(|e| e.from == *ty).count(),
+            })
+            .collect()
+    }
+
+    /// Every elementary cycle of at most `max_len` types, such as the
+    /// Fire -> Grass -> Water triangle of super-effective edges. Each
+    /// cycle starts at its type that comes first in the chart; self-loops
+    /// like Dragon -> Dragon are cycles of one.
+    pub fn cycles(&self, max_len: usize) -> Vec<Vec<T>> {
+        let index = |ty: &T| self.nodes.iter().position(|n| n == ty).unwrap();
+        let successors: Vec<Vec<usize>> = self
+            .nodes
+            .iter()
+            .map(|ty| {
+                let mut next: Vec<_> = self
+                    .edges
+                    .iter()
+                    .filter(|e| e.from == *ty)
+                    .map(|e| index(&e.to))
+                    .collect();
+                next.sort_unstable();
+                next.dedup();
+                next
+            })
+            .collect();
+
+        let mut cycles = Vec::new();
+        for start in 0..self.nodes.len() {
+            let mut path = vec![start];
+            self.extend_cycles(&successors, &mut path, max_len, &mut cycles);
+        }
+        cycles
+    }
+
+    fn extend_cycles(
+        &self,
+        successors: &[Vec<usize>],
+        path: &mut Vec<usize>,
+        max_len: usize,
+        cycles: &mut Vec<Vec<T>>,
+    ) {
+        let start = path[0];
+        for &next in &successors[*path.last().unwrap()] {
+            if next == start {
+                cycles.push(path.iter().map(|i| self.nodes[*i]).collect());
+            } else if next > start && !path.contains(&next) && path.len() < max_len {
+                path.push(next);
+                self.extend_cycles(successors, path, max_len, cycles);
+                path.pop();
+            }
+        }
+    }
+
+    /// Graphviz DOT source.
+    pub fn to_dot(&self, l10n: &Localization) -> String {
+        let mut out = String::from("digraph types {\n");
+        for ty in &self.nodes {
+            out += &format!(
+                "    {} [label={}];\n",
+                self.dot_id(ty),
+                quote_dot(&self.name(ty, l10n))
+            );
+        }
+        for edge in &self.edges {
+            out += &format!(
+                "    {} -> {} [label=\"{}\", color=\"{}\"{}];\n",
+                self.dot_id(&edge.from),
+                self.dot_id(&edge.to),
+                edge.rate,
+                edge.kind.color(),
+                if edge.kind == EdgeKind::Immune {
+                    ", style=dashed"
+                } else {
+                    ""
+                }
+            );
+        }
+        out += "}\n";
+        out
+    }
+
+    /// GraphML document with `kind` and `rate` edge data.
+    pub fn to_graphml(&self, l10n: &Localization) -> String {
+        let mut out = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+             <key id=\"name\" for=\"node\" attr.name=\"name\" attr.type=\"string\"/>\n  \
+             <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n  \
+             <key id=\"rate\" for=\"edge\" attr.name=\"rate\" attr.type=\"double\"/>\n  \
+             <graph id=\"types\" edgedefault=\"directed\">\n",
+        );
+        for ty in &self.nodes {
+            out += &format!(
+                "    <node id=\"{}\"><data key=\"name\">{}</data></node>\n",
+                escape_xml(&self.chart.identifier(ty)),
+                escape_xml(&self.name(ty, l10n))
+            );
+        }
+        for edge in &self.edges {
+            out += &format!(
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"kind\">{}</data>\
+                 <data key=\"rate\">{}</data></edge>\n",
+                escape_xml(&self.chart.identifier(&edge.from)),
+                escape_xml(&self.chart.identifier(&edge.to)),
+                edge.kind.to_str(),
+                edge.rate
+            );
+        }
+        out += "  </graph>\n</graphml>\n";
+        out
+    }
+
+    /// A standalone SVG drawing with the types on a circle.
+    pub fn to_svg(&self, l10n: &Localization) -> String {
+        const SIZE: f64 = 800.0;
+        const RADIUS: f64 = 320.0;
+        const NODE: f64 = 32.0;
+        let center = SIZE / 2.0;
+        let position = |i: usize| {
+            let angle = 2.0 * PI * i as f64 / self.nodes.len() as f64 - PI / 2.0;
+            (center + RADIUS * angle.cos(), center + RADIUS * angle.sin())
+        };
+        let index = |ty: &T| self.nodes.iter().position(|n| n == ty).unwrap();
+
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" \
+             viewBox=\"0 0 {0} {0}\">\n  <defs>\n",
+            SIZE
+        );
+        for kind in EdgeKind::kinds().iter() {
+            out += &format!(
+                "    <marker id=\"{}\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+                 markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">\
+                 <path d=\"M0,0 L10,5 L0,10 z\" fill=\"{}\"/></marker>\n",
+                kind.to_str(),
+                kind.color()
+            );
+        }
+        out += "  </defs>\n";
+
+        for edge in &self.edges {
+            let (x1, y1) = position(index(&edge.from));
+            let (x2, y2) = position(index(&edge.to));
+            let dash = if edge.kind == EdgeKind::Immune {
+                " stroke-dasharray=\"6,4\""
+            } else {
+                ""
+            };
+            let path = if edge.from == edge.to {
+                // A small loop outside the circle.
+                let (dx, dy) = ((x1 - center) / RADIUS, (y1 - center) / RADIUS);
+                let (ox, oy) = (x1 + dx * NODE, y1 + dy * NODE);
+                format!(
+                    "M{:.1},{:.1} C{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}",
+                    ox - dy * 8.0,
+                    oy + dx * 8.0,
+                    ox + dx * 40.0 - dy * 30.0,
+                    oy + dy * 40.0 + dx * 30.0,
+                    ox + dx * 40.0 + dy * 30.0,
+                    oy + dy * 40.0 - dx * 30.0,
+                    ox + dy * 8.0,
+                    oy - dx * 8.0
+                )
+            } else {
+                // Curve to the right of the direction of travel, so edges in
+                // both directions between two types stay apart, and stop at
+                // the node's border.
+                let (dx, dy) = (x2 - x1, y2 - y1);
+                let length = (dx * dx + dy * dy).sqrt();
+                let (ux, uy) = (dx / length, dy / length);
+                let (cx, cy) = (
+                    (x1 + x2) / 2.0 - uy * length * 0.15,
+                    (y1 + y2) / 2.0 + ux * length * 0.15,
+                );
+                let (ex, ey) = (x2 - cx, y2 - cy);
+                let end_length = (ex * ex + ey * ey).sqrt();
+                format!(
+                    "M{:.1},{:.1} Q{:.1},{:.1} {:.1},{:.1}",
+                    x1,
+                    y1,
+                    cx,
+                    cy,
+                    x2 - ex / end_length * NODE,
+                    y2 - ey / end_length * NODE
+                )
+            };
+            out += &format!(
+                "  <path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\"{} \
+                 marker-end=\"url(#{})\"><title>{} -> {}: {}</title></path>\n",
+                path,
+                edge.kind.color(),
+                dash,
+                edge.kind.to_str(),
+                escape_xml(&self.name(&edge.from, l10n)),
+                escape_xml(&self.name(&edge.to, l10n)),
+                edge.rate
+            );
+        }
+
+        for (i, ty) in self.nodes.iter().enumerate() {
+            let (x, y) = position(i);
+            out += &format!(
+                "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"#ffffff\" stroke=\"#212121\"/>\n  \
+                 <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"sans-serif\" font-size=\"12\" \
+                 text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                x,
+                y,
+                NODE,
+                x,
+                y,
+                escape_xml(&self.name(ty, l10n))
+            );
+        }
+        out += "</svg>\n";
+        out
+    }
+
+    fn name(&self, ty: &T, l10n: &Localization) -> String {
+        self.chart.name(ty, l10n.code())
+    }
+
+    /// The identifier as a quoted DOT id, so that distinct identifiers
+    /// stay distinct nodes.
+    fn dot_id(&self, ty: &T) -> String {
+        quote_dot(&self.chart.identifier(ty))
+    }
+}
+
+/// A quoted DOT string.
+fn quote_dot(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            '\n' => out += "\\n",
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+
+    #[test]
+    fn edges_and_degrees() {
+        let graph = TypeGraph::new(&Chart::builtin());
+        let immune = graph.filter(&[EdgeKind::Immune]);
+        assert_eq!(immune.edges.len(), 8);
+        let degrees = immune.degrees();
+        let ghost = degrees.iter().find(|d| d.ty == Ghost).unwrap();
+        assert_eq!((ghost.in_degree, ghost.out_degree), (2, 1));
+
+        let super_effective = graph.filter(&[EdgeKind::SuperEffective]);
+        let total_in: usize = super_effective.degrees().iter().map(|d| d.in_degree).sum();
+        assert_eq!(total_in, super_effective.edges.len());
+    }
+
+    #[test]
+    fn cycles() {
+        let graph = TypeGraph::new(&Chart::builtin()).filter(&[EdgeKind::SuperEffective]);
+        let cycles = graph.cycles(3);
+        assert!(cycles.contains(&vec![Fire, Grass, Water]));
+        assert!(cycles.contains(&vec![Dragon]));
+        assert!(cycles.iter().all(|cycle| cycle.len() <= 3));
+        assert!(!cycles.contains(&vec![Grass, Water, Fire]));
+    }
+
+    #[test]
+    fn formats() {
+        let graph = TypeGraph::new(&Chart::builtin()).filter(&[EdgeKind::Immune]);
+        let dot = graph.to_dot(&Localization::English);
+        assert!(dot.contains("\"normal\" -> \"ghost\" [label=\"0\""));
+        let graphml = graph.to_graphml(&Localization::Japanese);
+        assert!(graphml.contains("<data key=\"name\">ゴースト</data>"));
+        assert_eq!(graphml.matches("<edge ").count(), 8);
+        let svg = graph.to_svg(&Localization::English);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("fill=\"none\"").count(), 8);
+    }
+
+    #[test]
+    fn dot_quotes_custom_types() {
+        let mut chart = Chart::from_builtin(&Chart::neutral(&[]));
+        let dash = chart.add_type("a-b");
+        let underscore = chart.add_type("a_b");
+        chart.set_name(&dash, "en", "Say \"hi\"\\");
+        chart.set(&dash, &underscore, 2.0);
+        let dot = TypeGraph::new(&chart).to_dot(&Localization::English);
+        assert!(dot.contains("    \"a-b\" [label=\"Say \\\"hi\\\"\\\\\"];\n"));
+        assert!(dot.contains("    \"a_b\" [label=\"a_b\"];\n"));
+        assert!(dot.contains("    \"a-b\" -> \"a_b\" [label=\"2\""));
+    }
+}
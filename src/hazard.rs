@@ -0,0 +1,313 @@
+use crate::chart::Chart;
+use crate::field::{BattleField, Weather};
+use crate::generation::Generation;
+use crate::immunity::{type_rules, TypeRule};
+use crate::types::{BlockTypes, Type};
+
+/// Status inflicted by Toxic Spikes on switch-in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ToxicSpikes {
+    Unaffected,
+    Poisoned,
+    BadlyPoisoned,
+    /// A grounded Poison type removes the spikes.
+    Absorbed,
+}
+
+/// Chip damage in percent of max HP.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Chip {
+    pub stealth_rock: f64,
+    pub spikes: f64,
+    pub toxic_spikes: ToxicSpikes,
+    pub weather: f64,
+}
+
+impl Chip {
+    /// Damage taken when switching in.
+    pub fn switch_in(&self) -> f64 {
+        self.stealth_rock + self.spikes
+    }
+
+    /// Residual damage at the end of the first turn on the field.
+    pub fn first_turn(&self) -> f64 {
+        let poison = match self.toxic_spikes {
+            ToxicSpikes::Poisoned => 12.5,
+            ToxicSpikes::BadlyPoisoned => 6.25,
+            _ => 0.0,
+        };
+        self.weather + poison
+    }
+
+    pub fn total(&self) -> f64 {
+        self.switch_in() + self.first_turn()
+    }
+}
+
+/// Entry hazards and weather on one side of the field.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Hazards {
+    stealth_rock: bool,
+    spikes: u8,
+    toxic_spikes: u8,
+    weather: Option<Weather>,
+    generation: Generation,
+    field: BattleField,
+}
+
+impl Hazards {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stealth_rock(mut self) -> Self {
+        self.stealth_rock = true;
+        self
+    }
+
+    /// Layers of Spikes, up to 3.
+    pub fn spikes(mut self, layers: u8) -> Self {
+        self.spikes = layers.min(3);
+        self
+    }
+
+    /// Layers of Toxic Spikes, up to 2.
+    pub fn toxic_spikes(mut self, layers: u8) -> Self {
+        self.toxic_spikes = layers.min(2);
+        self
+    }
+
+    pub fn weather(mut self, weather: Weather) -> Self {
+        self.weather = Some(weather);
+        self
+    }
+
+    pub fn generation(mut self, generation: Generation) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Conditions on the defending side, such as Gravity grounding Flying
+    /// types.
+    pub fn field(mut self, field: BattleField) -> Self {
+        self.field = field;
+        self
+    }
+
+    /// Chip of a Pokémon that is airborne only if it has the Flying type.
+    pub fn chip<B: BlockTypes + ?Sized>(&self, chart: &Chart, types: &B) -> Chip {
+        let airborne = types.block_types().contains(&&Type::Flying);
+        self.chip_airborne(chart, types, airborne)
+    }
+
+    /// Chip of a Pokémon that is `airborne` before field conditions, e.g.
+    /// with Levitate or an Air Balloon.
+    pub fn chip_airborne<B: BlockTypes + ?Sized>(
+        &self,
+        chart: &Chart,
+        types: &B,
+        airborne: bool,
+    ) -> Chip {
+        let types = types.block_types();
+        let grounded = !airborne || self.field.is_grounded();
+
+        let stealth_rock = if self.stealth_rock {
+            12.5 * self.field.combat_n(chart, &Type::Rock, &types)
+        } else {
+            0.0
+        };
+
+        let spikes = match (grounded, self.spikes) {
+            (false, _) | (_, 0) => 0.0,
+            (_, 1) => 100.0 / 8.0,
+            (_, 2) => 100.0 / 6.0,
+            _ => 100.0 / 4.0,
+        };
+
+        let toxic_spikes = if !grounded || self.toxic_spikes == 0 {
+            ToxicSpikes::Unaffected
+        } else if types.contains(&&Type::Poison) {
+            ToxicSpikes::Absorbed
+        } else if self.has_rule(&types, TypeRule::PoisonImmune) {
+            ToxicSpikes::Unaffected
+        } else if self.toxic_spikes == 1 {
+            ToxicSpikes::Poisoned
+        } else {
+            ToxicSpikes::BadlyPoisoned
+        };
+
+        let weather = match self.weather {
+            Some(Weather::Sandstorm) if !self.has_rule(&types, TypeRule::SandstormImmune) => 6.25,
+            // Hail became snow in Gen 9, which deals no damage.
+            Some(Weather::Hail)
+                if self.generation < Generation::Gen9
+                    && !self.has_rule(&types, TypeRule::HailImmune) =>
+            {
+                6.25
+            }
+            _ => 0.0,
+        };
+
+        Chip {
+            stealth_rock,
+            spikes,
+            toxic_spikes,
+            weather,
+        }
+    }
+
+    pub fn team_chip<B: BlockTypes>(&self, chart: &Chart, team: &[B]) -> Vec<Chip> {
+        team.iter().map(|member| self.chip(chart, member)).collect()
+    }
+
+    /// Team member indices, most hazard-weak first.
+    pub fn rank<B: BlockTypes>(&self, chart: &Chart, team: &[B]) -> Vec<(usize, Chip)> {
+        let airborne: Vec<_> = team
+            .iter()
+            .map(|member| member.block_types().contains(&&Type::Flying))
+            .collect();
+        self.rank_airborne(chart, team, &airborne)
+    }
+
+    /// `rank` with whether each member is airborne before field
+    /// conditions, as in `chip_airborne`.
+    pub fn rank_airborne<B: BlockTypes>(
+        &self,
+        chart: &Chart,
+        team: &[B],
+        airborne: &[bool],
+    ) -> Vec<(usize, Chip)> {
+        let mut chips: Vec<_> = team
+            .iter()
+            .zip(airborne)
+            .map(|(member, airborne)| self.chip_airborne(chart, member, *airborne))
+            .enumerate()
+            .collect();
+        chips.sort_by(|(_, a), (_, b)| b.total().total_cmp(&a.total()));
+        chips
+    }
+
+    /// Sum of every member's chip, for comparing teams.
+    pub fn team_total<B: BlockTypes>(&self, chart: &Chart, team: &[B]) -> f64 {
+        self.team_chip(chart, team).iter().map(Chip::total).sum()
+    }
+
+    fn has_rule(&self, types: &[&Type], rule: TypeRule) -> bool {
+        types
+            .iter()
+            .any(|ty| type_rules(ty, self.generation).contains(&rule))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::Condition;
+    use crate::ruleset::Ruleset;
+    use crate::types::Type::*;
+
+    #[test]
+    fn stealth_rock() {
+        let chart = Chart::builtin();
+        let hazards = Hazards::new().stealth_rock();
+        assert_eq!(hazards.chip(&chart, &[&Fire, &Flying]).stealth_rock, 50.0);
+        assert_eq!(hazards.chip(&chart, &[&Fire]).stealth_rock, 25.0);
+        assert_eq!(hazards.chip(&chart, &[&Water]).stealth_rock, 12.5);
+        assert_eq!(hazards.chip(&chart, &[&Fight, &Ground]).stealth_rock, 3.125);
+    }
+
+    #[test]
+    fn spikes_need_grounded() {
+        let chart = Chart::builtin();
+        let hazards = Hazards::new().spikes(3);
+        assert_eq!(hazards.chip(&chart, &[&Water]).spikes, 25.0);
+        assert_eq!(hazards.chip(&chart, &[&Water, &Flying]).spikes, 0.0);
+    }
+
+    #[test]
+    fn grounding() {
+        let chart = Chart::builtin();
+        let hazards = Hazards::new().spikes(1).toxic_spikes(1);
+        let levitating = hazards.chip_airborne(&chart, &[&Water], true);
+        assert_eq!(levitating.spikes, 0.0);
+        assert_eq!(levitating.toxic_spikes, ToxicSpikes::Unaffected);
+
+        let gravity = hazards.field(BattleField::new().with(Condition::Gravity));
+        assert_eq!(gravity.chip(&chart, &[&Water, &Flying]).spikes, 12.5);
+        assert_eq!(gravity.chip_airborne(&chart, &[&Water], true).spikes, 12.5);
+        assert_eq!(
+            gravity.chip(&chart, &[&Poison, &Flying]).toxic_spikes,
+            ToxicSpikes::Absorbed
+        );
+    }
+
+    #[test]
+    fn stealth_rock_follows_chart() {
+        let hazards = Hazards::new().stealth_rock();
+        let mut custom = Chart::builtin();
+        custom.set(&Rock, &Steel, 2.0);
+        assert_eq!(hazards.chip(&custom, &[&Steel]).stealth_rock, 25.0);
+        let go = Chart::builtin().with_ruleset(Ruleset::Go);
+        assert_eq!(hazards.chip(&go, &[&Fire]).stealth_rock, 12.5 * 1.6);
+
+        let winds = hazards.field(BattleField::new().with(Condition::StrongWinds));
+        let chart = Chart::builtin();
+        assert_eq!(winds.chip(&chart, &[&Fire, &Flying]).stealth_rock, 25.0);
+        assert_eq!(winds.chip(&chart, &[&Flying]).stealth_rock, 12.5);
+    }
+
+    #[test]
+    fn toxic_spikes() {
+        let chart = Chart::builtin();
+        let hazards = Hazards::new().toxic_spikes(2);
+        assert_eq!(
+            hazards.chip(&chart, &[&Water]).toxic_spikes,
+            ToxicSpikes::BadlyPoisoned
+        );
+        assert_eq!(
+            hazards.chip(&chart, &[&Poison, &Dark]).toxic_spikes,
+            ToxicSpikes::Absorbed
+        );
+        assert_eq!(
+            hazards.chip(&chart, &[&Steel]).toxic_spikes,
+            ToxicSpikes::Unaffected
+        );
+        assert_eq!(
+            hazards.chip(&chart, &[&Poison, &Flying]).toxic_spikes,
+            ToxicSpikes::Unaffected
+        );
+    }
+
+    #[test]
+    fn weather() {
+        let chart = Chart::builtin();
+        let sand = Hazards::new().weather(Weather::Sandstorm);
+        assert_eq!(sand.chip(&chart, &[&Water]).weather, 6.25);
+        assert_eq!(sand.chip(&chart, &[&Water, &Ground]).weather, 0.0);
+
+        let hail = Hazards::new()
+            .weather(Weather::Hail)
+            .generation(Generation::Gen8);
+        assert_eq!(hail.chip(&chart, &[&Ice, &Water]).weather, 0.0);
+        assert_eq!(hail.chip(&chart, &[&Water]).weather, 6.25);
+        let snow = Hazards::new().weather(Weather::Hail);
+        assert_eq!(snow.chip(&chart, &[&Water]).weather, 0.0);
+    }
+
+    #[test]
+    fn rank_team() {
+        let chart = Chart::builtin();
+        let team = vec![vec![&Steel, &Ground], vec![&Fire, &Flying], vec![&Water]];
+        let hazards = Hazards::new().stealth_rock().spikes(1);
+        let order: Vec<_> = hazards
+            .rank(&chart, &team)
+            .iter()
+            .map(|(i, _)| *i)
+            .collect();
+        assert_eq!(order, vec![1, 2, 0]);
+        assert_eq!(
+            hazards.team_total(&chart, &team),
+            3.125 + 12.5 + 50.0 + 12.5 + 12.5
+        );
+    }
+}
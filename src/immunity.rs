@@ -0,0 +1,192 @@
+use crate::generation::Generation;
+use crate::types::{BlockTypes, Type};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+/// Effects granted by a type beyond damage multipliers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TypeRule {
+    BurnImmune,
+    ParalysisImmune,
+    FreezeImmune,
+    PoisonImmune,
+    PowderImmune,
+    LeechSeedImmune,
+    TrappingImmune,
+    PranksterImmune,
+    SandstormImmune,
+    HailImmune,
+    SandstormSpDefBoost,
+    SnowDefBoost,
+}
+
+impl TypeRule {
+    pub fn rules() -> [TypeRule; 12] {
+        use TypeRule::*;
+        [
+            BurnImmune,
+            ParalysisImmune,
+            FreezeImmune,
+            PoisonImmune,
+            PowderImmune,
+            LeechSeedImmune,
+            TrappingImmune,
+            PranksterImmune,
+            SandstormImmune,
+            HailImmune,
+            SandstormSpDefBoost,
+            SnowDefBoost,
+        ]
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        use TypeRule::*;
+        match *self {
+            BurnImmune => "can't be burned",
+            ParalysisImmune => "can't be paralyzed",
+            FreezeImmune => "can't be frozen",
+            PoisonImmune => "can't be poisoned",
+            PowderImmune => "ignores powder moves",
+            LeechSeedImmune => "ignores Leech Seed",
+            TrappingImmune => "can't be trapped",
+            PranksterImmune => "ignores Prankster status moves",
+            SandstormImmune => "ignores sandstorm damage",
+            HailImmune => "ignores hail damage",
+            SandstormSpDefBoost => "Sp. Def boosted in sandstorm",
+            SnowDefBoost => "Defense boosted in snow",
+        }
+    }
+}
+
+struct Rule {
+    ty: Type,
+    rule: TypeRule,
+    since: Generation,
+    until: Option<Generation>,
+}
+
+impl Rule {
+    fn new(ty: Type, rule: TypeRule, since: Generation) -> Self {
+        Self {
+            ty,
+            rule,
+            since,
+            until: None,
+        }
+    }
+
+    fn until(mut self, until: Generation) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    fn applies(&self, ty: &Type, generation: Generation) -> bool {
+        self.ty == *ty
+            && self.since <= generation
+            && self.until.is_none_or(|until| generation <= until)
+    }
+}
+
+/// Rules a single type grants in the given generation.
+pub fn type_rules(ty: &Type, generation: Generation) -> Vec<TypeRule> {
+    TYPE_RULES
+        .iter()
+        .filter(|rule| rule.applies(ty, generation))
+        .map(|rule| rule.rule)
+        .collect()
+}
+
+/// Rules granted by any of the types, in the latest generation.
+pub fn status_immunities<B: BlockTypes + ?Sized>(types: &B) -> Vec<TypeRule> {
+    status_immunities_in(types, Generation::latest())
+}
+
+pub fn status_immunities_in<B: BlockTypes + ?Sized>(
+    types: &B,
+    generation: Generation,
+) -> Vec<TypeRule> {
+    let types = types.block_types();
+    TypeRule::rules()
+        .iter()
+        .filter(|rule| {
+            types
+                .iter()
+                .any(|ty| type_rules(ty, generation).contains(rule))
+        })
+        .copied()
+        .collect()
+}
+
+/// Number of team members each rule applies to.
+pub fn team_immunities<B: BlockTypes>(
+    team: &[B],
+    generation: Generation,
+) -> HashMap<TypeRule, usize> {
+    let mut counts = HashMap::new();
+    for member in team {
+        for rule in status_immunities_in(member, generation) {
+            *counts.entry(rule).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+lazy_static! {
+    static ref TYPE_RULES: Vec<Rule> = {
+        use Generation::*;
+        use Type::*;
+        use TypeRule::*;
+        vec![
+            Rule::new(Fire, BurnImmune, Gen1),
+            Rule::new(Electric, ParalysisImmune, Gen6),
+            Rule::new(Ice, FreezeImmune, Gen1),
+            Rule::new(Ice, HailImmune, Gen3).until(Gen8),
+            Rule::new(Ice, SnowDefBoost, Gen9),
+            Rule::new(Poison, PoisonImmune, Gen1),
+            Rule::new(Steel, PoisonImmune, Gen2),
+            Rule::new(Grass, PowderImmune, Gen6),
+            Rule::new(Grass, LeechSeedImmune, Gen1),
+            Rule::new(Ghost, TrappingImmune, Gen6),
+            Rule::new(Dark, PranksterImmune, Gen7),
+            Rule::new(Rock, SandstormImmune, Gen2),
+            Rule::new(Ground, SandstormImmune, Gen2),
+            Rule::new(Steel, SandstormImmune, Gen2),
+            Rule::new(Rock, SandstormSpDefBoost, Gen4),
+        ]
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+
+    #[test]
+    fn rules_by_generation() {
+        assert_eq!(type_rules(&Electric, Generation::Gen5), vec![]);
+        assert_eq!(
+            type_rules(&Electric, Generation::Gen6),
+            vec![TypeRule::ParalysisImmune]
+        );
+        assert!(type_rules(&Ice, Generation::Gen8).contains(&TypeRule::HailImmune));
+        assert!(!type_rules(&Ice, Generation::Gen9).contains(&TypeRule::HailImmune));
+    }
+
+    #[test]
+    fn dual_type_immunities() {
+        assert_eq!(
+            status_immunities(&[&Steel, &Ground]),
+            vec![TypeRule::PoisonImmune, TypeRule::SandstormImmune]
+        );
+        assert_eq!(status_immunities(&[&Normal]), vec![]);
+    }
+
+    #[test]
+    fn team_counts() {
+        let team = vec![vec![&Steel, &Ground], vec![&Rock], vec![&Fire, &Flying]];
+        let counts = team_immunities(&team, Generation::Gen9);
+        assert_eq!(counts[&TypeRule::SandstormImmune], 2);
+        assert_eq!(counts[&TypeRule::BurnImmune], 1);
+        assert_eq!(counts.get(&TypeRule::ParalysisImmune), None);
+    }
+}
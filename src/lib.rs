@@ -1,4 +1,34 @@
 #[macro_use]
 extern crate maplit;
 
+pub mod balance;
+pub mod chart;
+pub mod cores;
+pub mod cover;
+mod csv;
+pub mod damage;
+pub mod diff;
+pub mod field;
+pub mod game;
+pub mod generation;
+pub mod generator;
+#[cfg(test)]
+mod golden;
+pub mod graph;
+pub mod hazard;
+pub mod immunity;
+pub mod moves;
+pub mod pokeapi;
+#[cfg(test)]
+mod properties;
+pub mod raid;
+pub mod ruleset;
+pub mod showdown;
+pub mod species;
+pub mod stats;
+pub mod suggest;
+pub mod team;
 pub mod types;
+pub mod typing;
+pub mod validate;
+pub mod weakness;
@@ -1,45 +1,1079 @@
+mod args;
+mod repl;
+
+use args::Args;
 use itertools::Itertools;
-use pokemon_type::types::Type;
+use pokemon_type::balance::Balance;
+use pokemon_type::chart::Chart;
+use pokemon_type::cores::CoreSearch;
+use pokemon_type::cover::CoverSearch;
+use pokemon_type::diff::ChartDiff;
+use pokemon_type::field::{BattleField, Condition, Weather};
+use pokemon_type::game::TypeGame;
+use pokemon_type::generation::Generation;
+use pokemon_type::generator::ChartGenerator;
+use pokemon_type::graph::{EdgeKind, TypeGraph};
+use pokemon_type::hazard::Hazards;
+use pokemon_type::immunity::status_immunities;
+use pokemon_type::moves::Movedex;
+use pokemon_type::pokeapi::PokeApiData;
+use pokemon_type::raid::raid_counters;
+use pokemon_type::ruleset::Ruleset;
+use pokemon_type::showdown::{
+    from_typechart, from_typechart_custom, parse_team, to_typechart_json, to_typechart_ts,
+};
+use pokemon_type::species::Pokedex;
+use pokemon_type::suggest::{
+    format_ranking, rank_typings, suggest_species, suggest_typings, Candidate, Scorer,
+};
+use pokemon_type::types::{ChartType, CustomType, Localization, Type};
+use pokemon_type::validate::{validate_typechart, Severity};
+use pokemon_type::weakness::{Matchup, WeaknessReport};
 
 use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+/// The default command: every typing of three types of the selected chart,
+/// best first.
+fn list_types<T: CommandType>(chart: &Chart<T>) {
+    let ranking = rank_typings(chart, 3, Scorer::LogRates);
+    let mut out = format!("types: {}\n", ranking.len());
+    for (types, score) in &ranking {
+        out += &format!("{} = {}\n", labels(chart, types), score);
+    }
+    print!("{}", out);
+}
+
+/// `rank [--size N] [--scorer X]`: `list_types` with other settings, e.g.
+/// `--size 4 --scorer multipliers` for the checked-in `data4`.
+fn show_ranking(chart: &Chart, args: &[String]) {
+    let mut size = 3;
+    let mut scorer = Scorer::LogRates;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => size = flag_value(arg, args.next()),
+            "--scorer" => {
+                let name = args.next().map(String::as_str).unwrap_or_default();
+                scorer = Scorer::from_str(name).unwrap_or_else(|| {
+                    eprintln!("unknown scorer: {}", name);
+                    process::exit(1)
+                });
+            }
+            _ => {
+                eprintln!("unknown option: {}", arg);
+                process::exit(1)
+            }
+        }
+    }
+    print!("{}", format_ranking(&rank_typings(chart, size, scorer)));
+}
+
+/// Types of a token, species taking their typing of `generation`.
+fn resolve_in(dex: &Pokedex, token: &str, generation: Generation) -> Vec<Type> {
+    dex.resolve(token, generation).unwrap_or_else(|| {
+        match dex.get(token) {
+            Some(species) => eprintln!(
+                "{} is not in generation {}",
+                species.name(),
+                generation.number()
+            ),
+            None => eprintln!("unknown type or species: {}", token),
+        }
+        process::exit(1)
+    })
+}
+
+fn resolve(dex: &Pokedex, token: &str) -> Vec<Type> {
+    resolve_in(dex, token, Generation::latest())
+}
+
+/// What the analysis commands need from a chart's types: how to print
+/// them, how to read them from the command line, and the species-based
+/// options, which only built-in charts have.
+trait CommandType: ChartType {
+    fn label(chart: &Chart<Self>, ty: &Self) -> String;
+
+    fn resolve(dex: &Pokedex, chart: &Chart<Self>, token: &str) -> Vec<Self>;
+
+    fn suggest_species(
+        chart: &Chart<Self>,
+        team: &[Vec<Self>],
+        scorer: Scorer,
+        dex: &Pokedex,
+    ) -> Vec<Candidate<Self>>;
+
+    fn cover_species(
+        search: CoverSearch<Self>,
+        dex: &Pokedex,
+        generation: Generation,
+    ) -> CoverSearch<Self>;
+
+    fn generation_chart(generation: Generation) -> Chart<Self>;
+
+    fn load(path: &Path) -> Chart<Self>;
+}
+
+impl CommandType for Type {
+    fn label(_: &Chart, ty: &Type) -> String {
+        format!("{:?}", ty)
+    }
+
+    fn resolve(dex: &Pokedex, _: &Chart, token: &str) -> Vec<Type> {
+        resolve(dex, token)
+    }
+
+    fn suggest_species(
+        chart: &Chart,
+        team: &[Vec<Type>],
+        scorer: Scorer,
+        dex: &Pokedex,
+    ) -> Vec<Candidate> {
+        suggest_species(chart, team, scorer, dex, Generation::latest())
+    }
+
+    fn cover_species(search: CoverSearch, dex: &Pokedex, generation: Generation) -> CoverSearch {
+        search.species(dex, generation)
+    }
+
+    fn generation_chart(generation: Generation) -> Chart {
+        Chart::generation(generation)
+    }
+
+    fn load(path: &Path) -> Chart {
+        match load_chart(path) {
+            LoadedChart::Builtin(chart) => chart,
+            LoadedChart::Custom(_) => {
+                eprintln!("{} has custom types; load it with --chart", path.display());
+                process::exit(1)
+            }
+        }
+    }
+}
+
+impl CommandType for CustomType {
+    fn label(chart: &Chart<CustomType>, ty: &CustomType) -> String {
+        chart.name(ty, "en")
+    }
+
+    /// Types separated by `/`, by identifier or name.
+    fn resolve(_: &Pokedex, chart: &Chart<CustomType>, token: &str) -> Vec<CustomType> {
+        token
+            .split('/')
+            .map(|name| {
+                chart.find(name).unwrap_or_else(|| {
+                    eprintln!("unknown type: {}", name);
+                    process::exit(1)
+                })
+            })
+            .collect()
+    }
+
+    fn suggest_species(
+        _: &Chart<CustomType>,
+        _: &[Vec<CustomType>],
+        _: Scorer,
+        _: &Pokedex,
+    ) -> Vec<Candidate<CustomType>> {
+        builtin_only("--species")
+    }
+
+    fn cover_species(
+        _: CoverSearch<CustomType>,
+        _: &Pokedex,
+        _: Generation,
+    ) -> CoverSearch<CustomType> {
+        builtin_only("--species")
+    }
+
+    fn generation_chart(generation: Generation) -> Chart<CustomType> {
+        Chart::from_builtin(&Chart::generation(generation))
+    }
+
+    fn load(path: &Path) -> Chart<CustomType> {
+        match load_chart(path) {
+            LoadedChart::Builtin(chart) => Chart::from_builtin(&chart),
+            LoadedChart::Custom(chart) => chart,
+        }
+    }
+}
 
-fn list_types() {
-    let types = Type::types();
-    let c: Vec<(Vec<&Type>, f64)> = types
+fn builtin_only(what: &str) -> ! {
+    eprintln!("{} needs a chart of built-in types", what);
+    process::exit(1)
+}
+
+/// Types as `[A, B]`.
+fn labels<T: CommandType>(chart: &Chart<T>, types: &[T]) -> String {
+    format!(
+        "[{}]",
+        types.iter().map(|ty| T::label(chart, ty)).join(", ")
+    )
+}
+
+fn show_type(dex: &Pokedex, chart: &Chart, args: &[String]) {
+    let types: Vec<_> = args
         .iter()
-        .combinations(3)
-        .into_iter()
-        .map(|t| {
-            (
-                t.clone(),
-                Type::blocks_rates(&t)
-                    .into_iter()
-                    .fold(0.0, |a, (_, b)| a + if b == 0.0 { b } else { b.log2() }),
-            )
-        })
-        .sorted_by(|(_, a), (_, b)| a.partial_cmp(&b).unwrap())
+        .flat_map(|token| resolve(dex, token))
+        .unique()
         .collect();
-    println!("types: {}", c.len());
-    for x in c {
-        println!("{:?} = {}", x.0, x.1);
+    let types: Vec<_> = types.iter().collect();
+    let rates = chart.blocks_rates(&types[..]);
+    println!("{:#?}", rates);
+
+    let rules = status_immunities(&types[..]);
+    if !rules.is_empty() {
+        println!("Rules:");
+        for rule in rules {
+            println!("    {}", rule.to_str());
+        }
+    }
+
+    for condition in Condition::conditions().iter() {
+        let field = BattleField::new().with(*condition);
+        let changed: Vec<_> = chart
+            .types()
+            .iter()
+            .map(|ty| {
+                let without = chart.combat_n(ty, &types[..]);
+                (*ty, without, field.combat_n(chart, ty, &types[..]))
+            })
+            .filter(|(_, without, with)| without != with)
+            .collect();
+        if changed.is_empty() {
+            continue;
+        }
+        println!("{}:", condition.to_str());
+        for (ty, without, with) in changed {
+            println!("    {:?}: {} -> {}", ty, without, with);
+        }
+    }
+}
+
+fn list_species(dex: &Pokedex, args: &[String]) {
+    let types: Vec<_> = args.iter().flat_map(|token| resolve(dex, token)).collect();
+    let types: Vec<_> = types.iter().collect();
+    for species in dex.with_types(&types, Generation::latest()) {
+        println!("{}", species.name());
     }
 }
 
-fn show_type(types: &[String]) {
-    let types: Vec<_> = types
+/// `hazards <member>... [--stealth-rock] [--spikes N] [--toxic-spikes N]
+/// [--weather W] [--gen N] [--field A,B] [--levitate <member>]`. Without
+/// any hazard or weather, Stealth Rock, 3 Spikes and 2 Toxic Spikes.
+fn show_hazards(dex: &Pokedex, chart: &Chart, args: &[String]) {
+    let mut members = Vec::new();
+    let mut levitating = Vec::new();
+    let (mut stealth_rock, mut spikes, mut toxic_spikes) = (false, 0u8, 0u8);
+    let mut weather = None;
+    let mut generation = Generation::latest();
+    let mut field = BattleField::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--stealth-rock" => stealth_rock = true,
+            "--spikes" => spikes = flag_value(arg, args.next()),
+            "--toxic-spikes" => toxic_spikes = flag_value(arg, args.next()),
+            "--weather" => {
+                let name = args.next().map(String::as_str).unwrap_or_default();
+                weather = Some(Weather::from_str(name).unwrap_or_else(|| {
+                    eprintln!("unknown weather: {}", name);
+                    process::exit(1)
+                }));
+            }
+            "--gen" => {
+                generation =
+                    Generation::from_number(flag_value(arg, args.next())).unwrap_or_else(|| {
+                        eprintln!("invalid value for --gen");
+                        process::exit(1)
+                    })
+            }
+            "--field" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+                for name in value.split(',') {
+                    let condition = Condition::from_str(name).unwrap_or_else(|| {
+                        eprintln!("unknown condition: {}", name);
+                        process::exit(1)
+                    });
+                    field = field.with(condition);
+                }
+            }
+            "--levitate" => levitating.extend(args.next()),
+            _ => members.push(arg.clone()),
+        }
+    }
+    if !stealth_rock && spikes == 0 && toxic_spikes == 0 && weather.is_none() {
+        stealth_rock = true;
+        spikes = 3;
+        toxic_spikes = 2;
+    }
+
+    let mut hazards = Hazards::new()
+        .spikes(spikes)
+        .toxic_spikes(toxic_spikes)
+        .generation(generation)
+        .field(field);
+    if stealth_rock {
+        hazards = hazards.stealth_rock();
+    }
+    if let Some(weather) = weather {
+        hazards = hazards.weather(weather);
+    }
+
+    let team: Vec<_> = members
         .iter()
-        .map(|ty| Type::from_str(ty).unwrap().clone())
+        .map(|member| resolve_in(dex, member, generation))
         .collect();
-    let types: Vec<_> = types.iter().map(|ty| ty).collect();
-    println!("{:#?}", Type::blocks_rates(&types[..]));
+    let team: Vec<Vec<&Type>> = team.iter().map(|types| types.iter().collect()).collect();
+    let airborne: Vec<_> = team
+        .iter()
+        .zip(&members)
+        .map(|(types, member)| types.contains(&&Type::Flying) || levitating.contains(&member))
+        .collect();
+    let ranked = hazards.rank_airborne(chart, &team, &airborne);
+    for (i, chip) in &ranked {
+        println!("{}:", members[*i]);
+        if stealth_rock {
+            println!("    Stealth Rock: {}%", chip.stealth_rock);
+        }
+        if spikes > 0 {
+            println!("    Spikes x{}: {}%", spikes.min(3), chip.spikes);
+        }
+        if toxic_spikes > 0 {
+            println!(
+                "    Toxic Spikes x{}: {:?}",
+                toxic_spikes.min(2),
+                chip.toxic_spikes
+            );
+        }
+        if let Some(weather) = weather {
+            println!("    {}: {}%", weather.to_str(), chip.weather);
+        }
+    }
+    println!(
+        "team total: {}%",
+        ranked.iter().map(|(_, chip)| chip.total()).sum::<f64>()
+    );
 }
 
-fn main() {
-    let args: Vec<_> = env::args().skip(1).collect();
+/// Members weak to one type that make it a stacked weakness.
+const STACKED_WEAKNESS: usize = 3;
+
+fn print_weakness_report<T: CommandType>(
+    chart: &Chart<T>,
+    report: &WeaknessReport<T>,
+    threshold: usize,
+) {
+    println!("type      weak  4x  neutral  resist  immune");
+    for m in &report.matchups {
+        println!(
+            "{:<9} {:>4} {:>3} {:>8} {:>7} {:>7}",
+            T::label(chart, &m.attack_type),
+            m.weak,
+            m.very_weak,
+            m.neutral,
+            m.resist,
+            m.immune
+        );
+    }
+    let names = |matchups: Vec<&Matchup<T>>| {
+        matchups
+            .iter()
+            .map(|m| T::label(chart, &m.attack_type))
+            .join(" ")
+    };
+    let stacked = report.stacked(threshold);
+    if !stacked.is_empty() {
+        println!("stacked weaknesses (>= {}): {}", threshold, names(stacked));
+    }
+    let holes = report.holes();
+    if !holes.is_empty() {
+        println!("no resist: {}", names(holes));
+    }
+}
 
-    if args.len() == 0 {
-        list_types();
+fn show_report<T: CommandType>(dex: &Pokedex, chart: &Chart<T>, args: &[String]) {
+    let (threshold, members) = match args {
+        [flag, n, members @ ..] if flag == "--stack" => (
+            n.parse().unwrap_or_else(|_| {
+                eprintln!("invalid threshold: {}", n);
+                process::exit(1)
+            }),
+            members,
+        ),
+        _ => (STACKED_WEAKNESS, args),
+    };
+    let team: Vec<_> = members
+        .iter()
+        .map(|member| T::resolve(dex, chart, member))
+        .collect();
+    print_weakness_report(chart, &WeaknessReport::new(chart, &team), threshold);
+}
+
+fn show_suggestions<T: CommandType>(dex: &Pokedex, chart: &Chart<T>, args: &[String]) {
+    let mut scorer = Scorer::Weaknesses;
+    let mut species = false;
+    let mut members = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--species" => species = true,
+            "--scorer" => {
+                let name = args.next().map(String::as_str).unwrap_or_default();
+                scorer = Scorer::from_str(name).unwrap_or_else(|| {
+                    eprintln!("unknown scorer: {}", name);
+                    process::exit(1)
+                });
+            }
+            _ => members.push(T::resolve(dex, chart, arg)),
+        }
+    }
+
+    let candidates = if species {
+        T::suggest_species(chart, &members, scorer, dex)
     } else {
-        show_type(&args[..])
+        suggest_typings(chart, &members, scorer)
+    };
+    for candidate in candidates.iter().take(20) {
+        let types = candidate
+            .types
+            .iter()
+            .map(|ty| T::label(chart, ty))
+            .join("/");
+        match &candidate.species {
+            Some(name) => print!("{} ({})", name, types),
+            None => print!("{}", types),
+        }
+        println!(" = {} ({:+})", candidate.score, candidate.improvement);
+        if !candidate.patches.is_empty() {
+            println!("    patches: {}", labels(chart, &candidate.patches));
+        }
+        if !candidate.adds.is_empty() {
+            println!("    adds: {}", labels(chart, &candidate.adds));
+        }
+    }
+}
+
+/// Parses the value after a command line flag.
+fn flag_value<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| {
+            eprintln!("invalid value for {}", flag);
+            process::exit(1)
+        })
+}
+
+fn show_cores<T: CommandType>(chart: &Chart<T>, args: &[String]) {
+    let mut search = CoreSearch::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        search = match arg.as_str() {
+            "--size" => search.size(flag_value(arg, args.next())),
+            "--uncovered" => search.allow_uncovered(flag_value(arg, args.next())),
+            "--stealth-rock" => search.stealth_rock(flag_value(arg, args.next())),
+            _ => {
+                eprintln!("unknown option: {}", arg);
+                process::exit(1)
+            }
+        };
+    }
+
+    for core in search.find(chart).iter().take(20) {
+        let members: Vec<_> = core
+            .members
+            .iter()
+            .map(|types| types.iter().map(|ty| T::label(chart, ty)).join("/"))
+            .collect();
+        println!(
+            "{} = {} (tightness {}, Stealth Rock {})",
+            members.join(" + "),
+            core.score(),
+            core.tightness,
+            core.penalty
+        );
+        for (member, ty) in &core.uncovered {
+            println!(
+                "    uncovered: {} weak to {}",
+                members[*member],
+                T::label(chart, ty)
+            );
+        }
+    }
+}
+
+fn show_cover<T: CommandType>(dex: &Pokedex, chart: &Chart<T>, args: &[String]) {
+    let mut search = CoverSearch::new();
+    let mut species = false;
+    let mut generation = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--threshold" => search = search.threshold(flag_value(arg, args.next())),
+            "--species" => species = true,
+            "--gen" => {
+                generation = Some(
+                    Generation::from_number(flag_value(arg, args.next())).unwrap_or_else(|| {
+                        eprintln!("invalid value for --gen");
+                        process::exit(1)
+                    }),
+                )
+            }
+            _ => {
+                eprintln!("unknown option: {}", arg);
+                process::exit(1)
+            }
+        }
+    }
+    let chart = match generation {
+        Some(generation) => T::generation_chart(generation).with_ruleset(chart.ruleset().clone()),
+        None => chart.clone(),
+    };
+    if species {
+        search = T::cover_species(search, dex, generation.unwrap_or_default());
+    }
+
+    let cover = search.solve(&chart);
+    for types in &cover.uncoverable {
+        println!("uncoverable: {}", labels(&chart, types));
+    }
+    println!("size: {}", cover.size().unwrap_or(0));
+    for solution in &cover.solutions {
+        println!("{}", labels(&chart, solution));
+    }
+}
+
+fn show_game<T: CommandType>(chart: &Chart<T>, args: &[String]) {
+    let mut game = TypeGame::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        game = match arg.as_str() {
+            "--dual" => game.dual(),
+            "--immune" => game.immune(flag_value(arg, args.next())),
+            _ => {
+                eprintln!("unknown option: {}", arg);
+                process::exit(1)
+            }
+        };
+    }
+
+    let equilibrium = game.solve(chart);
+    println!("value: {}", equilibrium.value);
+    println!("attack:");
+    for (ty, p) in &equilibrium.attack {
+        println!("    {}: {:.4}", T::label(chart, ty), p);
+    }
+    println!("defense:");
+    for (types, p) in &equilibrium.defense {
+        println!("    {}: {:.4}", labels(chart, types), p);
+    }
+}
+
+fn show_balance<T: CommandType>(chart: &Chart<T>, args: &[String]) {
+    let balance = Balance::new(chart);
+    if let [command, path] = args {
+        if command == "diff" {
+            let other = Balance::new(&T::load(Path::new(path)));
+            println!("type      offense  defense   rank");
+            for change in balance.diff(&other) {
+                println!(
+                    "{:<9} {:>+7.3} {:>+8.3} {:>+6} {}",
+                    T::label(chart, &change.ty),
+                    change.offense,
+                    change.defense,
+                    change.best_rank,
+                    match change.strength() {
+                        s if s > 0.0 => "stronger",
+                        s if s < 0.0 => "weaker",
+                        _ => "",
+                    }
+                );
+            }
+            return;
+        }
+    }
+    if !args.is_empty() {
+        eprintln!("usage: balance [diff <chart>]");
+        process::exit(1)
+    }
+
+    println!("type       SE  resisted  blocked  weak  resist  immune  offense  defense  rank");
+    for t in &balance.types {
+        println!(
+            "{:<9} {:>3} {:>9} {:>8} {:>5} {:>7} {:>7} {:>8.3} {:>8.3} {:>5}",
+            T::label(chart, &t.ty),
+            t.super_effective,
+            t.resisted,
+            t.blocked,
+            t.weaknesses,
+            t.resistances,
+            t.immunities,
+            t.offense,
+            t.defense,
+            t.best_rank
+        );
+    }
+    println!("symmetry: {:.3}", balance.symmetry);
+    println!("entropy: {:.3}", balance.entropy);
+    for (ty, by) in &balance.dominated {
+        println!(
+            "dominated: {} by {}",
+            T::label(chart, ty),
+            T::label(chart, by)
+        );
+    }
+}
+
+fn show_graph<T: CommandType>(chart: &Chart<T>, args: &[String]) {
+    let mut kinds = EdgeKind::kinds().to_vec();
+    let mut l10n = Localization::English;
+    let mut max_len = 3;
+    let mut args = args.iter();
+    let output = args.next().map(String::as_str).unwrap_or("dot");
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--kinds" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+                kinds = value
+                    .split(',')
+                    .map(|kind| {
+                        EdgeKind::from_str(kind).unwrap_or_else(|| {
+                            eprintln!("unknown edge kind: {}", kind);
+                            process::exit(1)
+                        })
+                    })
+                    .collect();
+            }
+            "--lang" => {
+                l10n = match args.next().map(String::as_str) {
+                    Some("ja") => Localization::Japanese,
+                    Some("en") => Localization::English,
+                    _ => {
+                        eprintln!("invalid value for --lang");
+                        process::exit(1)
+                    }
+                }
+            }
+            "--max" => max_len = flag_value(arg, args.next()),
+            _ => {
+                eprintln!("unknown option: {}", arg);
+                process::exit(1)
+            }
+        }
+    }
+
+    let graph = TypeGraph::new(chart).filter(&kinds);
+    match output {
+        "dot" => print!("{}", graph.to_dot(&l10n)),
+        "graphml" => print!("{}", graph.to_graphml(&l10n)),
+        "svg" => print!("{}", graph.to_svg(&l10n)),
+        "cycles" => {
+            for cycle in graph.cycles(max_len) {
+                let names: Vec<_> = cycle.iter().map(|ty| chart.name(ty, l10n.code())).collect();
+                println!("{} -> {}", names.join(" -> "), names[0]);
+            }
+        }
+        "degrees" => {
+            println!("type      in  out");
+            for degree in graph.degrees() {
+                println!(
+                    "{:<9} {:>3} {:>4}",
+                    chart.name(&degree.ty, l10n.code()),
+                    degree.in_degree,
+                    degree.out_degree
+                );
+            }
+        }
+        _ => {
+            eprintln!("unknown graph output: {}", output);
+            process::exit(1)
+        }
+    }
+}
+
+fn show_raid<T: CommandType>(dex: &Pokedex, chart: &Chart<T>, args: &[String]) {
+    let mut boss = Vec::new();
+    let mut charged_moves = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--moves" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+                for token in value.split(',') {
+                    charged_moves.extend(T::resolve(dex, chart, token));
+                }
+            }
+            _ => boss.extend(T::resolve(dex, chart, arg)),
+        }
+    }
+    if boss.is_empty() {
+        eprintln!("usage: raid <boss type or species>... [--moves A,B]");
+        process::exit(1)
+    }
+
+    for counter in raid_counters(chart, &boss, &charged_moves).iter().take(20) {
+        println!(
+            "{} = {:.3} ({} {}x, takes {}x)",
+            counter.types.iter().map(|ty| T::label(chart, ty)).join("/"),
+            counter.score(),
+            T::label(chart, &counter.attack_type),
+            counter.offense,
+            counter.defense
+        );
+    }
+}
+
+fn matchup_kind(rate: f64) -> Option<&'static str> {
+    if rate == 0.0 {
+        Some("immune")
+    } else if rate < 1.0 {
+        Some("resist")
+    } else if rate > 1.0 {
+        Some("weak")
+    } else {
+        None
+    }
+}
+
+fn show_team(dex: &Pokedex, chart: &Chart, path: &str) {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        process::exit(1)
+    });
+    let team = parse_team(&text, dex, Movedex::bundled()).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        process::exit(1)
+    });
+
+    println!("Defense:");
+    for member in &team.members {
+        let rates = chart.blocks_rates(member.types());
+        println!("    {} {:?}", member.name(), member.types());
+        for kind in ["weak", "resist", "immune"].iter() {
+            let types: Vec<_> = chart
+                .types()
+                .iter()
+                .filter(|ty| matchup_kind(rates[ty]) == Some(kind))
+                .map(|ty| format!("{:?}", ty))
+                .collect();
+            if !types.is_empty() {
+                println!("        {}: {}", kind, types.join(" "));
+            }
+        }
+    }
+
+    println!("Weaknesses:");
+    print_weakness_report(chart, &team.weakness_report(chart), STACKED_WEAKNESS);
+
+    println!("Coverage:");
+    for (block_type, attack_type, rate) in team.coverage(chart) {
+        match attack_type {
+            Some(attack_type) => println!("    {:?}: {} ({:?})", block_type, rate, attack_type),
+            None => println!("    {:?}: -", block_type),
+        }
+    }
+}
+
+fn export_showdown<T: ChartType>(chart: &Chart<T>, format: Option<&str>) {
+    match format {
+        None | Some("ts") => print!("{}", to_typechart_ts(chart)),
+        Some("json") => print!("{}", to_typechart_json(chart)),
+        Some(format) => {
+            eprintln!("unknown format: {}", format);
+            process::exit(1)
+        }
+    }
+}
+
+fn generate_chart(args: &[String]) {
+    let mut args = args.iter();
+    let size = flag_value("size", args.next());
+    let mut generator = ChartGenerator::new(size);
+    let mut format = None;
+    while let Some(arg) = args.next() {
+        generator = match arg.as_str() {
+            "--seed" => generator.seed(flag_value(arg, args.next())),
+            "--immunities" => generator.immunities(flag_value(arg, args.next())),
+            "--symmetry" => generator.symmetry(flag_value(arg, args.next())),
+            "--attempts" => generator.attempts(flag_value(arg, args.next())),
+            "--density" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+                match value
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<Result<Vec<f64>, _>>()
+                {
+                    Ok(density) if density.len() == 2 => generator.density(density[0], density[1]),
+                    _ => {
+                        eprintln!("invalid value for --density");
+                        process::exit(1)
+                    }
+                }
+            }
+            "--format" => {
+                format = args.next().map(String::as_str);
+                generator
+            }
+            _ => {
+                eprintln!("unknown option: {}", arg);
+                process::exit(1)
+            }
+        };
+    }
+    let chart = generator.generate().unwrap_or_else(|| {
+        eprintln!("no chart met the constraints; try more --attempts");
+        process::exit(1)
+    });
+    export_showdown(&chart, format);
+}
+
+/// A chart given with `--chart`: built-in types only, or with custom ones.
+enum LoadedChart {
+    Builtin(Chart),
+    Custom(Chart<CustomType>),
+}
+
+impl LoadedChart {
+    /// A built-in generation like "gen5", or a chart file.
+    fn load(source: &str) -> Self {
+        let generation = source
+            .strip_prefix("gen")
+            .and_then(|n| n.parse().ok())
+            .and_then(Generation::from_number);
+        match generation {
+            Some(generation) => LoadedChart::Builtin(Chart::generation(generation)),
+            None => load_chart(Path::new(source)),
+        }
+    }
+
+    fn into_custom(self) -> Chart<CustomType> {
+        match self {
+            LoadedChart::Builtin(chart) => Chart::from_builtin(&chart),
+            LoadedChart::Custom(chart) => chart,
+        }
+    }
+
+    fn with_ruleset(self, ruleset: Ruleset) -> Self {
+        match self {
+            LoadedChart::Builtin(chart) => LoadedChart::Builtin(chart.with_ruleset(ruleset)),
+            LoadedChart::Custom(chart) => LoadedChart::Custom(chart.with_ruleset(ruleset)),
+        }
+    }
+}
+
+fn load_chart(path: &Path) -> LoadedChart {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path.display(), e);
+        process::exit(1)
+    });
+    let exit = |e| -> ! {
+        eprintln!("{}: {}", path.display(), e);
+        process::exit(1)
+    };
+    let custom = from_typechart_custom(&text, &Chart::builtin()).unwrap_or_else(|e| exit(e));
+    if custom.types().iter().all(|ty| ty.builtin().is_some()) {
+        LoadedChart::Builtin(from_typechart(&text, &Chart::builtin()).unwrap_or_else(|e| exit(e)))
+    } else {
+        LoadedChart::Custom(custom)
+    }
+}
+
+fn print_diff<T: ChartType>(old: &Chart<T>, new: &Chart<T>, format: &str, depth: usize) {
+    let diff = ChartDiff::new(old, new, depth);
+    match format {
+        "text" => print!("{}", diff.to_text()),
+        "json" => print!("{}", diff.to_json()),
+        "markdown" => print!("{}", diff.to_markdown()),
+        _ => {
+            eprintln!("unknown format: {}", format);
+            process::exit(1)
+        }
+    }
+}
+
+/// `diff <old> [new]`, each a chart file or "gen5" and the like; `new`
+/// defaults to the current chart.
+fn show_diff(chart: LoadedChart, args: &[String]) {
+    let mut sources = Vec::new();
+    let mut format = "text".to_string();
+    let mut depth = 10;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = flag_value(arg, args.next()),
+            "--top" => depth = flag_value(arg, args.next()),
+            _ => sources.push(arg),
+        }
+    }
+    let (old, new) = match sources.as_slice() {
+        [old] => (LoadedChart::load(old), chart),
+        [old, new] => (LoadedChart::load(old), LoadedChart::load(new)),
+        _ => {
+            eprintln!("usage: diff <old> [new] [--format text|json|markdown] [--top N]");
+            process::exit(1)
+        }
+    };
+    match (old, new) {
+        (LoadedChart::Builtin(old), LoadedChart::Builtin(new)) => {
+            print_diff(&old, &new, &format, depth)
+        }
+        (old, new) => print_diff(&old.into_custom(), &new.into_custom(), &format, depth),
+    }
+}
+
+/// Runs the commands that work over any chart; false if `args` is not one.
+fn run_analysis<T: CommandType>(dex: &Pokedex, chart: &Chart<T>, args: &[String]) -> bool {
+    match args.first().map(String::as_str) {
+        Some("raid") => show_raid(dex, chart, &args[1..]),
+        Some("balance") => show_balance(chart, &args[1..]),
+        Some("graph") => show_graph(chart, &args[1..]),
+        Some("game") => show_game(chart, &args[1..]),
+        Some("cover") => show_cover(dex, chart, &args[1..]),
+        Some("cores") => show_cores(chart, &args[1..]),
+        Some("suggest") => show_suggestions(dex, chart, &args[1..]),
+        Some("report") => show_report(dex, chart, &args[1..]),
+        Some("showdown") => export_showdown(chart, args.get(1).map(String::as_str)),
+        _ => return false,
+    }
+    true
+}
+
+fn check_pokeapi(dir: &str) {
+    let data = PokeApiData::import(Path::new(dir)).unwrap_or_else(|e| {
+        eprintln!("{}: {}", dir, e);
+        process::exit(1)
+    });
+
+    for ty in Type::types().iter() {
+        if let Some(names) = data.names.get(ty) {
+            let names: Vec<_> = names
+                .iter()
+                .sorted()
+                .map(|(language, name)| format!("{}={}", language, name))
+                .collect();
+            println!("{:?}: {}", ty, names.join(" "));
+        }
+    }
+    println!("species: {}", data.species_types.len());
+
+    let mismatches = data.mismatches();
+    for (generation, attack, block, imported, builtin) in &mismatches {
+        println!(
+            "mismatch: gen {} {:?} -> {:?}: imported {}, built-in {}",
+            generation.number(),
+            attack,
+            block,
+            imported,
+            builtin
+        );
+    }
+    if !mismatches.is_empty() {
+        process::exit(1);
+    }
+}
+
+/// Exits with 1 if the chart is invalid, or has any problem with
+/// `--strict`, and with 2 if it cannot be read.
+fn validate_chart(args: &[String]) {
+    let (path, strict) = match args {
+        [path] => (path, false),
+        [path, flag] if flag == "--strict" => (path, true),
+        _ => {
+            eprintln!("usage: validate <chart-file> [--strict]");
+            process::exit(2)
+        }
+    };
+    let text = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        process::exit(2)
+    });
+
+    let problems = validate_typechart(&text);
+    for problem in &problems {
+        println!(
+            "{}:{}: {}: {}",
+            path,
+            problem.line,
+            problem.severity.to_str(),
+            problem.message
+        );
+    }
+    let invalid = problems
+        .iter()
+        .filter(|p| p.severity == Severity::Invalid)
+        .count();
+    if !problems.is_empty() {
+        println!("{} invalid, {} unusual", invalid, problems.len() - invalid);
+    }
+    if invalid > 0 || (strict && !problems.is_empty()) {
+        process::exit(1);
+    }
+}
+
+fn main() {
+    let args = Args::parse(env::args().skip(1));
+    let loaded;
+    let dex = match &args.pokeapi {
+        Some(dir) => {
+            loaded = Pokedex::from_pokeapi(dir).unwrap_or_else(|e| {
+                eprintln!("{}: {}", dir.display(), e);
+                process::exit(1)
+            });
+            &loaded
+        }
+        None => Pokedex::bundled(),
+    };
+    let mut chart = match &args.chart {
+        Some(path) => load_chart(path),
+        None => LoadedChart::Builtin(Chart::builtin()),
+    };
+    if let Some(name) = &args.ruleset {
+        let ruleset = Ruleset::from_str(name).unwrap_or_else(|| {
+            eprintln!("unknown ruleset: {}", name);
+            process::exit(1)
+        });
+        chart = chart.with_ruleset(ruleset);
+    } else if args.positional.first().map(String::as_str) == Some("raid") {
+        // Raids are a Pokémon GO mode.
+        chart = chart.with_ruleset(Ruleset::Go);
+    }
+    let args = &args.positional;
+
+    match args.first().map(String::as_str) {
+        None => match &chart {
+            LoadedChart::Builtin(chart) => list_types(chart),
+            LoadedChart::Custom(chart) => list_types(chart),
+        },
+        Some("species") => list_species(dex, &args[1..]),
+        Some("pokeapi") if args.len() == 2 => check_pokeapi(&args[1]),
+        Some("generate") => generate_chart(&args[1..]),
+        Some("validate") => validate_chart(&args[1..]),
+        Some("repl") => match chart {
+            LoadedChart::Builtin(chart) => repl::run(dex, chart).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                process::exit(1)
+            }),
+            LoadedChart::Custom(_) => builtin_only("repl"),
+        },
+        Some("diff") => show_diff(chart, &args[1..]),
+        _ => match &chart {
+            LoadedChart::Builtin(chart) => {
+                if !run_analysis(dex, chart, args) {
+                    match args[0].as_str() {
+                        "team" if args.len() == 2 => show_team(dex, chart, &args[1]),
+                        "hazards" => show_hazards(dex, chart, &args[1..]),
+                        "rank" => show_ranking(chart, &args[1..]),
+                        _ => show_type(dex, chart, &args[..]),
+                    }
+                }
+            }
+            LoadedChart::Custom(chart) => {
+                if !run_analysis(dex, chart, args) {
+                    builtin_only(&args[0])
+                }
+            }
+        },
     }
 }
@@ -0,0 +1,122 @@
+use crate::csv::{parse_number, Table};
+use crate::damage::{Category, Move};
+use crate::species::{display_name, normalize};
+use crate::types::Type;
+use lazy_static::lazy_static;
+use std::io;
+
+/// A move from the move table. Status moves have no category.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveData {
+    /// PokeAPI-style identifier, e.g. "u-turn".
+    pub identifier: String,
+    pub ty: Type,
+    pub category: Option<Category>,
+    /// Base power; 0 for status moves and moves with variable power.
+    pub power: u32,
+}
+
+impl MoveData {
+    /// Display name such as "Close-Combat".
+    pub fn name(&self) -> String {
+        display_name(&self.identifier)
+    }
+
+    pub fn is_status(&self) -> bool {
+        self.category.is_none()
+    }
+
+    /// The move for the damage calculator, unless it is a status move.
+    pub fn to_move(&self) -> Option<Move> {
+        self.category
+            .map(|category| Move::new(self.ty, self.power, category))
+    }
+}
+
+/// A table of moves.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Movedex {
+    moves: Vec<MoveData>,
+}
+
+impl Movedex {
+    pub fn new(moves: Vec<MoveData>) -> Self {
+        Self { moves }
+    }
+
+    /// The moves bundled with the crate, mostly ones seen in competitive
+    /// play.
+    pub fn bundled() -> &'static Movedex {
+        &BUNDLED
+    }
+
+    pub fn moves(&self) -> &[MoveData] {
+        &self.moves
+    }
+
+    /// Looks up a move by name, ignoring case, spaces and punctuation.
+    /// "Hidden Power [Fire]" finds Hidden Power with the given type.
+    pub fn get(&self, name: &str) -> Option<MoveData> {
+        let id = normalize(name);
+        if let Some(ty) = id
+            .strip_prefix("hidden-power-")
+            .and_then(Type::from_identifier)
+        {
+            let mut hidden_power = self.get("hidden-power")?;
+            hidden_power.ty = ty;
+            return Some(hidden_power);
+        }
+        self.moves.iter().find(|m| m.identifier == id).cloned()
+    }
+
+    fn from_bundled_csv(text: &str) -> io::Result<Movedex> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let table = Table::parse(text).ok_or_else(|| invalid("empty move table".into()))?;
+        let identifier = table.column("identifier")?;
+        let ty = table.column("type")?;
+        let category = table.column("category")?;
+        let power = table.column("power")?;
+
+        let mut moves = Vec::new();
+        for row in &table.rows {
+            moves.push(MoveData {
+                identifier: row[identifier].clone(),
+                ty: Type::from_identifier(&row[ty])
+                    .ok_or_else(|| invalid(format!("unknown type `{}`", row[ty])))?,
+                category: match row[category].as_str() {
+                    "physical" => Some(Category::Physical),
+                    "special" => Some(Category::Special),
+                    "status" => None,
+                    other => return Err(invalid(format!("unknown category `{}`", other))),
+                },
+                power: parse_number(&row[power])?,
+            });
+        }
+        Ok(Movedex { moves })
+    }
+}
+
+lazy_static! {
+    static ref BUNDLED: Movedex = Movedex::from_bundled_csv(include_str!("moves.csv")).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+
+    #[test]
+    fn lookup() {
+        let dex = Movedex::bundled();
+        let earthquake = dex.get("Earthquake").unwrap();
+        assert_eq!(earthquake.ty, Ground);
+        assert_eq!(
+            earthquake.to_move(),
+            Some(Move::new(Ground, 100, Category::Physical))
+        );
+        assert_eq!(dex.get("U-turn").unwrap().name(), "U-Turn");
+        assert!(dex.get("Stealth Rock").unwrap().is_status());
+        assert_eq!(dex.get("Hidden Power [Fire]").unwrap().ty, Fire);
+        assert_eq!(dex.get("Splash"), None);
+    }
+}
@@ -0,0 +1,313 @@
+use crate::chart::Chart;
+use crate::csv::{parse_number, Table};
+use crate::generation::Generation;
+use crate::types::{Localization, Type};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+/// Type of a PokeAPI `type_id`. Ids 1 to 18 follow the order of
+/// `Type::types()`; the others (Stellar, ???, Shadow) have no `Type`.
+pub(crate) fn pokeapi_type(id: &str) -> Option<Type> {
+    let id: usize = id.trim().parse().ok()?;
+    Type::types().get(id.checked_sub(1)?).copied()
+}
+
+/// Type data imported from a PokeAPI CSV dump: `types.csv`,
+/// `type_efficacy.csv`, `type_names.csv` and `pokemon_types.csv`, plus
+/// `type_efficacy_past.csv`, `languages.csv` and `pokemon.csv` when present.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PokeApiData {
+    introduced: HashMap<Type, Generation>,
+    efficacy: HashMap<(Type, Type), f64>,
+    /// Earlier multipliers, each valid up to and including its generation.
+    past_efficacy: Vec<(Generation, Type, Type, f64)>,
+    /// Localized names keyed by type and language identifier, e.g. "en".
+    pub names: HashMap<Type, HashMap<String, String>>,
+    /// Typing of every Pokémon, keyed by identifier (or id when
+    /// `pokemon.csv` is missing).
+    pub species_types: HashMap<String, Vec<Type>>,
+}
+
+impl PokeApiData {
+    pub fn import(dir: &Path) -> io::Result<PokeApiData> {
+        let mut data = PokeApiData::default();
+
+        let types = Table::read(&dir.join("types.csv"))?;
+        let (id, generation) = (types.column("id")?, types.column("generation_id")?);
+        for row in &types.rows {
+            if let Some(ty) = pokeapi_type(&row[id]) {
+                data.introduced
+                    .insert(ty, parse_generation(&row[generation])?);
+            }
+        }
+
+        let efficacy = Table::read(&dir.join("type_efficacy.csv"))?;
+        let (attack, block, factor) = efficacy_columns(&efficacy)?;
+        for row in &efficacy.rows {
+            if let (Some(a), Some(b)) = (pokeapi_type(&row[attack]), pokeapi_type(&row[block])) {
+                data.efficacy.insert((a, b), parse_factor(&row[factor])?);
+            }
+        }
+
+        let past_path = dir.join("type_efficacy_past.csv");
+        if past_path.exists() {
+            let past = Table::read(&past_path)?;
+            let (attack, block, factor) = efficacy_columns(&past)?;
+            let generation = past.column("generation_id")?;
+            for row in &past.rows {
+                if let (Some(a), Some(b)) = (pokeapi_type(&row[attack]), pokeapi_type(&row[block]))
+                {
+                    data.past_efficacy.push((
+                        parse_generation(&row[generation])?,
+                        a,
+                        b,
+                        parse_factor(&row[factor])?,
+                    ));
+                }
+            }
+        }
+
+        let languages = read_id_map(&dir.join("languages.csv"))?;
+        let names = Table::read(&dir.join("type_names.csv"))?;
+        let (type_id, language, name) = (
+            names.column("type_id")?,
+            names.column("local_language_id")?,
+            names.column("name")?,
+        );
+        for row in &names.rows {
+            if let Some(ty) = pokeapi_type(&row[type_id]) {
+                data.names
+                    .entry(ty)
+                    .or_default()
+                    .entry(language_code(&languages, &row[language]))
+                    .or_insert_with(|| row[name].clone());
+            }
+        }
+
+        let pokemon = read_id_map(&dir.join("pokemon.csv"))?;
+        let pokemon_types = Table::read(&dir.join("pokemon_types.csv"))?;
+        let (pokemon_id, type_id, slot) = (
+            pokemon_types.column("pokemon_id")?,
+            pokemon_types.column("type_id")?,
+            pokemon_types.column("slot")?,
+        );
+        let mut slots: HashMap<String, Vec<(u32, Type)>> = HashMap::new();
+        for row in &pokemon_types.rows {
+            if let Some(ty) = pokeapi_type(&row[type_id]) {
+                let key = pokemon
+                    .get(&row[pokemon_id])
+                    .cloned()
+                    .unwrap_or_else(|| row[pokemon_id].clone());
+                slots
+                    .entry(key)
+                    .or_default()
+                    .push((parse_number(&row[slot])?, ty));
+            }
+        }
+        data.species_types = slots
+            .into_iter()
+            .map(|(key, mut types)| {
+                types.sort_by_key(|(slot, _)| *slot);
+                (key, types.into_iter().map(|(_, ty)| ty).collect())
+            })
+            .collect();
+
+        Ok(data)
+    }
+
+    /// The imported chart for the given generation, with past efficacy
+    /// applied and only the types that existed then.
+    pub fn chart(&self, generation: Generation) -> Chart {
+        let types: Vec<_> = Type::types()
+            .iter()
+            .filter(|ty| {
+                self.introduced
+                    .get(ty)
+                    .is_some_and(|introduced| *introduced <= generation)
+            })
+            .copied()
+            .collect();
+        let mut chart = Chart::neutral(&types);
+        for ((a, b), rate) in &self.efficacy {
+            chart.set(a, b, *rate);
+        }
+
+        // Apply the most recent past entry that still covers `generation`,
+        // so walk them from the latest generation backwards.
+        let mut past: Vec<_> = self
+            .past_efficacy
+            .iter()
+            .filter(|(until, ..)| generation <= *until)
+            .collect();
+        past.sort_by_key(|(until, ..)| Reverse(*until));
+        for (_, a, b, rate) in past {
+            chart.set(a, b, *rate);
+        }
+        for (ty, names) in self.names.iter().filter(|(ty, _)| types.contains(ty)) {
+            for (language, name) in names {
+                chart.set_name(ty, language, name);
+            }
+        }
+        chart
+    }
+
+    /// Cells where the imported chart disagrees with the built-in one, for
+    /// every generation, as `(generation, attack, block, imported, builtin)`.
+    pub fn mismatches(&self) -> Vec<(Generation, Type, Type, f64, f64)> {
+        Generation::generations()
+            .iter()
+            .flat_map(|generation| {
+                self.chart(*generation)
+                    .mismatches(&Chart::generation(*generation))
+                    .into_iter()
+                    .map(move |(a, b, imported, builtin)| (*generation, a, b, imported, builtin))
+            })
+            .collect()
+    }
+
+    pub fn name(&self, ty: &Type, language: &str) -> Option<&str> {
+        self.names.get(ty)?.get(language).map(String::as_str)
+    }
+}
+
+fn efficacy_columns(table: &Table) -> io::Result<(usize, usize, usize)> {
+    Ok((
+        table.column("damage_type_id")?,
+        table.column("target_type_id")?,
+        table.column("damage_factor")?,
+    ))
+}
+
+/// PokeAPI stores multipliers as percentages.
+fn parse_factor(s: &str) -> io::Result<f64> {
+    Ok(parse_number::<f64>(s)? / 100.0)
+}
+
+fn parse_generation(s: &str) -> io::Result<Generation> {
+    Generation::from_number(parse_number(s)?).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid generation `{}`", s),
+        )
+    })
+}
+
+/// The code of a `languages.csv` id, with languages such as "ja-Hrkt"
+/// under their `Localization` code.
+pub(crate) fn language_code(languages: &HashMap<String, String>, id: &str) -> String {
+    let language = languages.get(id).map_or(id, String::as_str);
+    Localization::from_code(language).map_or(language.to_string(), |l10n| l10n.code().to_string())
+}
+
+/// `id` to `identifier` from an optional table.
+pub(crate) fn read_id_map(path: &Path) -> io::Result<HashMap<String, String>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let table = Table::read(path)?;
+    let (id, identifier) = (table.column("id")?, table.column("identifier")?);
+    Ok(table
+        .rows
+        .iter()
+        .map(|row| (row[id].clone(), row[identifier].clone()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+    use std::fs;
+
+    fn write_dump(dir: &Path, efficacy: &str) {
+        fs::create_dir_all(dir).unwrap();
+        let mut types = String::from("id,identifier,generation_id,damage_class_id\n");
+        for (i, ty) in Type::types().iter().enumerate() {
+            types += &format!(
+                "{},{},{},\n",
+                i + 1,
+                ty.identifier(),
+                Generation::introduced(ty).number()
+            );
+        }
+        types += "10001,unknown,2,\n";
+        fs::write(dir.join("types.csv"), types).unwrap();
+        fs::write(dir.join("type_efficacy.csv"), efficacy).unwrap();
+        fs::write(
+            dir.join("type_efficacy_past.csv"),
+            "damage_type_id,target_type_id,damage_factor,generation_id\n\
+             8,9,50,5\n17,9,50,5\n7,4,200,1\n4,7,200,1\n8,14,0,1\n15,10,100,1\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("type_names.csv"),
+            "type_id,local_language_id,name\n11,1,みず\n11,9,Water\n10001,9,???\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("languages.csv"),
+            "id,iso639,iso3166,identifier,official,order\n1,ja,jp,ja-Hrkt,1,1\n9,en,us,en,1,7\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("pokemon_types.csv"),
+            "pokemon_id,type_id,slot\n130,3,2\n130,11,1\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("pokemon.csv"),
+            "id,identifier,species_id\n130,gyarados,130\n",
+        )
+        .unwrap();
+    }
+
+    fn builtin_efficacy() -> String {
+        let mut efficacy = String::from("damage_type_id,target_type_id,damage_factor\n");
+        for (i, a) in Type::types().iter().enumerate() {
+            for (j, b) in Type::types().iter().enumerate() {
+                efficacy += &format!("{},{},{}\n", i + 1, j + 1, Type::combat1(a, b) * 100.0);
+            }
+        }
+        efficacy
+    }
+
+    #[test]
+    fn pokeapi_type_ids() {
+        assert_eq!(pokeapi_type("1"), Some(Normal));
+        assert_eq!(pokeapi_type("18"), Some(Fairy));
+        assert_eq!(pokeapi_type("10001"), None);
+    }
+
+    #[test]
+    fn import_matches_builtin() {
+        let dir = std::env::temp_dir().join(format!("pokemon_type_api_{}", std::process::id()));
+        write_dump(&dir, &builtin_efficacy());
+        let data = PokeApiData::import(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(data.mismatches(), vec![]);
+        assert_eq!(data.chart(Generation::Gen9), Chart::builtin());
+        assert_eq!(data.chart(Generation::Gen1).combat1(&Ice, &Fire), 1.0);
+        assert_eq!(data.name(&Water, "ja"), Some("みず"));
+        assert_eq!(data.chart(Generation::Gen9).name(&Water, "ja"), "みず");
+        assert_eq!(data.species_types["gyarados"], vec![Water, Flying]);
+    }
+
+    #[test]
+    fn report_mismatches() {
+        let dir = std::env::temp_dir().join(format!("pokemon_type_api_mm_{}", std::process::id()));
+        let efficacy = builtin_efficacy().replace("\n13,5,0\n", "\n13,5,50\n");
+        write_dump(&dir, &efficacy);
+        let data = PokeApiData::import(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let mismatches = data.mismatches();
+        assert_eq!(mismatches.len(), Generation::generations().len());
+        assert_eq!(
+            mismatches[0],
+            (Generation::Gen1, Electric, Ground, 0.5, 0.0)
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Consistency properties checked exhaustively over every type and typing
+//! of every built-in chart, ruleset and loaded chart.
+
+use crate::chart::Chart;
+use crate::generation::Generation;
+use crate::generator::ChartGenerator;
+use crate::ruleset::Ruleset;
+use crate::showdown::{from_typechart, from_typechart_custom, to_typechart_ts};
+use crate::types::{ChartType, CustomType, Localization, Type};
+use itertools::Itertools;
+
+/// Built-in charts of every generation and ruleset, and each of them
+/// exported to Showdown and loaded back.
+fn builtin_charts() -> Vec<(String, Chart)> {
+    let mut charts = Vec::new();
+    for generation in Generation::generations().iter() {
+        let chart = Chart::generation(*generation);
+        let loaded = from_typechart(&to_typechart_ts(&chart), &Chart::neutral(&[])).unwrap();
+        charts.push((format!("gen{}", generation.number()), chart));
+        charts.push((format!("gen{} loaded", generation.number()), loaded));
+    }
+    for ruleset in Ruleset::rulesets().iter() {
+        charts.push((
+            ruleset.to_str().to_string(),
+            Chart::builtin().with_ruleset(ruleset.clone()),
+        ));
+    }
+    charts
+}
+
+/// Charts with types beyond the built-in ones, loaded back from Showdown
+/// too, and a generated one.
+fn custom_charts() -> Vec<(String, Chart<CustomType>)> {
+    let gen3 = Chart::from_builtin(&Chart::generation(Generation::Gen3))
+        .with_unknown()
+        .with_shadow();
+    let latest = Chart::from_builtin(&Chart::builtin()).with_stellar();
+    let loaded = from_typechart_custom(&to_typechart_ts(&gen3), &Chart::builtin()).unwrap();
+    let generated = ChartGenerator::new(20).seed(1).generate().unwrap();
+    vec![
+        ("gen3 with ??? and Shadow".to_string(), gen3),
+        ("gen9 with Stellar".to_string(), latest),
+        ("gen3 with ??? and Shadow loaded".to_string(), loaded),
+        ("generated".to_string(), generated),
+    ]
+}
+
+fn check_combat_n<T: ChartType>(name: &str, chart: &Chart<T>) {
+    let multiplicative = [Ruleset::Mainline, Ruleset::Go].contains(chart.ruleset());
+    for a in chart.types() {
+        let typings = (1..=3).flat_map(|size| chart.types().iter().combinations(size));
+        for typing in typings {
+            let rate = chart.combat_n(a, &typing);
+            for permutation in typing.iter().copied().permutations(typing.len()) {
+                assert_eq!(chart.combat_n(a, &permutation), rate, "{}: {:?}", name, a);
+            }
+            if multiplicative {
+                let product: f64 = typing.iter().map(|b| chart.combat1(a, b)).product();
+                assert!(
+                    (product - rate).abs() <= rate * 1e-12,
+                    "{}: {:?} against {:?}",
+                    name,
+                    a,
+                    typing
+                );
+            }
+        }
+        for b in chart.types() {
+            assert_eq!(chart.combat_n(a, &[b]), chart.combat1(a, b), "{}", name);
+        }
+    }
+}
+
+fn check_rates<T: ChartType>(name: &str, chart: &Chart<T>) {
+    for a in chart.types() {
+        let block_rates = chart.block_rates(a);
+        assert_eq!(chart.blocks_rates(&[a]), block_rates, "{}: {:?}", name, a);
+        for b in chart.types() {
+            assert_eq!(
+                chart.attack_rates(a)[b],
+                chart.block_rates(b)[a],
+                "{}",
+                name
+            );
+        }
+    }
+}
+
+/// Inverting twice gives back every matchup but immunities, which an
+/// Inverse Battle turns into weaknesses.
+fn check_inverse<T: ChartType>(name: &str, chart: &Chart<T>) {
+    let (inverse, twice) = (chart.inverse(), chart.inverse().inverse());
+    for a in chart.types() {
+        for b in chart.types() {
+            let rate = chart.relation(a, b);
+            if rate == 0.0 {
+                assert_eq!(inverse.relation(a, b), 2.0, "{}: {:?} -> {:?}", name, a, b);
+            } else {
+                assert_eq!(twice.relation(a, b), rate, "{}: {:?} -> {:?}", name, a, b);
+            }
+        }
+    }
+}
+
+fn check_names<T: ChartType>(name: &str, chart: &Chart<T>) {
+    for ty in chart.types() {
+        assert_eq!(chart.find(&chart.identifier(ty)), Some(*ty), "{}", name);
+        for l10n in Localization::localizations().iter() {
+            let localized = chart.name(ty, l10n.code());
+            assert_eq!(chart.find(&localized), Some(*ty), "{}: {}", name, localized);
+        }
+    }
+}
+
+fn check<T: ChartType>(charts: &[(String, Chart<T>)], property: fn(&str, &Chart<T>)) {
+    for (name, chart) in charts {
+        property(name, chart);
+    }
+}
+
+#[test]
+fn combat_n_is_order_independent() {
+    check(&builtin_charts(), check_combat_n);
+    check(&custom_charts(), check_combat_n);
+}
+
+#[test]
+fn rates_agree() {
+    check(&builtin_charts(), check_rates);
+    check(&custom_charts(), check_rates);
+}
+
+#[test]
+fn inverse_twice() {
+    check(&builtin_charts(), check_inverse);
+    check(&custom_charts(), check_inverse);
+    // Without immunities, inverting twice is the identity.
+    let chart = ChartGenerator::new(18).immunities(0).generate().unwrap();
+    assert_eq!(chart.inverse().inverse(), chart);
+    assert_ne!(chart.inverse(), chart);
+}
+
+#[test]
+fn inverse_immunities() {
+    use crate::types::Type::*;
+    let inverse = Chart::builtin().inverse();
+    assert_eq!(inverse.relation(&Normal, &Ghost), 2.0);
+    assert_eq!(inverse.relation(&Ground, &Flying), 2.0);
+}
+
+#[test]
+fn names_round_trip() {
+    check(&builtin_charts(), check_names);
+    check(&custom_charts(), check_names);
+    for ty in Type::types().iter() {
+        assert_eq!(Type::from_str(ty.to_str(&Localization::English)), Some(*ty));
+        assert_eq!(Type::from_identifier(ty.identifier()), Some(*ty));
+        for l10n in Localization::localizations().iter() {
+            assert_eq!(Type::from_localized(ty.to_str(l10n), l10n), Some(*ty));
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::chart::Chart;
+use crate::suggest::typings;
+use crate::types::{ChartType, Type};
+
+/// A typing ranked as a raid counter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaidCounter<T: ChartType = Type> {
+    pub types: Vec<T>,
+    /// The counter's best attacking type, one of its own.
+    pub attack_type: T,
+    /// Multiplier of `attack_type` against the boss.
+    pub offense: f64,
+    /// Highest multiplier the counter takes from the boss's charged moves.
+    pub defense: f64,
+}
+
+impl<T: ChartType> RaidCounter<T> {
+    /// Damage dealt relative to the worst damage taken; higher is better.
+    /// A counter that cannot hurt the boss scores 0, one immune to every
+    /// charged move that can scores infinity.
+    pub fn score(&self) -> f64 {
+        if self.offense == 0.0 {
+            0.0
+        } else {
+            self.offense / self.defense
+        }
+    }
+}
+
+/// Ranks every mono and dual typing against a raid boss, best first.
+/// Counters are assumed to attack with a move of one of their own types.
+/// Without known charged moves, the boss is assumed to use its own types.
+/// A boss without types has no counters.
+pub fn raid_counters<T: ChartType>(
+    chart: &Chart<T>,
+    boss: &[T],
+    charged_moves: &[T],
+) -> Vec<RaidCounter<T>> {
+    if boss.is_empty() {
+        return Vec::new();
+    }
+    let charged_moves = if charged_moves.is_empty() {
+        boss
+    } else {
+        charged_moves
+    };
+    let mut counters: Vec<_> = typings(chart)
+        .into_iter()
+        .map(|types| {
+            let (attack_type, offense) = types
+                .iter()
+                .map(|ty| (*ty, chart.combat_n(ty, boss)))
+                .fold((types[0], f64::MIN), |best, candidate| {
+                    if candidate.1 > best.1 {
+                        candidate
+                    } else {
+                        best
+                    }
+                });
+            let defense = charged_moves
+                .iter()
+                .map(|ty| chart.combat_n(ty, &types))
+                .fold(f64::MIN, f64::max);
+            RaidCounter {
+                types,
+                attack_type,
+                offense,
+                defense,
+            }
+        })
+        .collect();
+    counters.sort_by(|a, b| {
+        b.score()
+            .total_cmp(&a.score())
+            .then(b.offense.total_cmp(&a.offense))
+    });
+    counters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ruleset::Ruleset;
+    use crate::types::Type::*;
+
+    #[test]
+    fn groudon() {
+        let chart = Chart::builtin().with_ruleset(Ruleset::Go);
+        let counters = raid_counters(&chart, &[Ground], &[Fire, Rock]);
+        assert_eq!(counters.len(), 171);
+        let best = &counters[0];
+        assert_eq!(best.offense, 1.6);
+        assert!(best.defense < 1.0);
+        assert!(counters.windows(2).all(|w| w[0].score() >= w[1].score()));
+
+        let electric = counters.iter().find(|c| c.types == vec![Electric]).unwrap();
+        assert_eq!(electric.offense, 0.390625);
+    }
+
+    #[test]
+    fn ghost_boss() {
+        // Normal neither hurts Ghost nor is hurt by it.
+        for ruleset in [Ruleset::Mainline, Ruleset::LegendsArceus].iter() {
+            let chart = Chart::builtin().with_ruleset(ruleset.clone());
+            let counters = raid_counters(&chart, &[Ghost], &[]);
+            assert_eq!(counters.len(), 171);
+            assert!(counters.iter().all(|c| !c.score().is_nan()));
+            let normal = counters.iter().find(|c| c.types == vec![Normal]).unwrap();
+            assert_eq!(
+                (normal.offense, normal.defense, normal.score()),
+                (0.0, 0.0, 0.0)
+            );
+            assert!(counters.windows(2).all(|w| w[0].score() >= w[1].score()));
+        }
+        // Dark/Normal hits Ghost and takes nothing back.
+        let counters = raid_counters(&Chart::builtin(), &[Ghost], &[]);
+        assert_eq!(counters[0].score(), f64::INFINITY);
+        assert!(raid_counters(&Chart::builtin(), &[], &[Fire]).is_empty());
+    }
+
+    #[test]
+    fn defaults_to_boss_types() {
+        let chart = Chart::builtin().with_ruleset(Ruleset::Go);
+        assert_eq!(
+            raid_counters(&chart, &[Dragon], &[]),
+            raid_counters(&chart, &[Dragon], &[Dragon])
+        );
+    }
+}
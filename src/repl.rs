@@ -0,0 +1,469 @@
+use itertools::Itertools;
+use pokemon_type::chart::Chart;
+use pokemon_type::generation::Generation;
+use pokemon_type::ruleset::Ruleset;
+use pokemon_type::species::Pokedex;
+use pokemon_type::types::{Localization, Type};
+use pokemon_type::weakness::WeaknessReport;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+
+const COMMANDS: [&str; 8] = ["def", "atk", "team", "lang", "gen", "help", "quit", "exit"];
+const TEAM_COMMANDS: [&str; 5] = ["add", "remove", "clear", "list", "report"];
+const STACKED_WEAKNESS: usize = 3;
+
+const HELP: &str = "\
+def <type|species>...      multipliers taken, e.g. `def Water Ground`
+atk <type>                 multipliers dealt, e.g. `atk Ice`
+team add <type|species>    add a member, e.g. `team add Steel/Fairy`
+team remove <n>            remove the n-th member
+team clear | list | report
+lang en|ja                 language of names and completion; species
+                           names need a PokeAPI dump (`--pokeapi <dir>`)
+gen <1-9>                  switch to a generation's chart
+quit";
+
+/// State of an interactive session: the chart, language and team that
+/// commands work on.
+pub struct Session<'a> {
+    dex: &'a Pokedex,
+    ruleset: Ruleset,
+    generation: Generation,
+    chart: Chart,
+    l10n: Localization,
+    /// Members as entered, resolved again when the generation changes.
+    team: Vec<String>,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(dex: &'a Pokedex, chart: Chart) -> Self {
+        Self {
+            dex,
+            ruleset: chart.ruleset().clone(),
+            generation: Generation::latest(),
+            chart,
+            l10n: Localization::English,
+            team: Vec::new(),
+        }
+    }
+
+    /// Runs one command line and returns what to print, or an error
+    /// message.
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => Ok(String::new()),
+            ["help"] => Ok(HELP.to_string()),
+            ["def", tokens @ ..] if !tokens.is_empty() => {
+                let mut types = Vec::new();
+                for token in tokens {
+                    types.extend(self.resolve(token)?);
+                }
+                let types: Vec<_> = types.into_iter().unique().collect();
+                Ok(format!(
+                    "{}\n{}",
+                    self.labels(&types),
+                    self.grouped(&self.chart.blocks_rates(&types))
+                ))
+            }
+            ["atk", token] => match self.resolve(token)?.as_slice() {
+                [ty] => Ok(self.grouped(&self.chart.attack_rates(ty))),
+                _ => Err("atk takes a single type".to_string()),
+            },
+            ["team"] | ["team", "list"] => self.team_list(),
+            ["team", "add", token] => {
+                let types = self.resolve(token)?;
+                self.team.push(token.to_string());
+                Ok(format!(
+                    "{}. {} ({})",
+                    self.team.len(),
+                    token,
+                    self.labels(&types)
+                ))
+            }
+            ["team", "remove", n] => {
+                let i = n
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|i| (1..=self.team.len()).contains(i))
+                    .ok_or_else(|| format!("no member {}", n))?;
+                Ok(format!("removed {}", self.team.remove(i - 1)))
+            }
+            ["team", "clear"] => {
+                self.team.clear();
+                Ok("team cleared".to_string())
+            }
+            ["team", "report"] => self.team_report(),
+            ["lang"] => Ok(self.l10n.code().to_string()),
+            ["lang", code] => {
+                self.l10n = Localization::from_code(code)
+                    .ok_or_else(|| format!("unknown language: {}", code))?;
+                let code = self.l10n.code();
+                let named = self
+                    .dex
+                    .species()
+                    .iter()
+                    .any(|species| species.names.contains_key(code));
+                if named || self.l10n == Localization::English {
+                    Ok(format!("language: {}", code))
+                } else {
+                    Ok(format!(
+                        "language: {} (types only: species names need a PokeAPI dump, \
+                         see `--pokeapi <dir>`)",
+                        code
+                    ))
+                }
+            }
+            ["gen"] => Ok(self.generation.number().to_string()),
+            ["gen", n] => {
+                self.generation = n
+                    .parse()
+                    .ok()
+                    .and_then(Generation::from_number)
+                    .ok_or_else(|| format!("unknown generation: {}", n))?;
+                self.chart = Chart::generation(self.generation).with_ruleset(self.ruleset.clone());
+                Ok(format!("generation {}", self.generation.number()))
+            }
+            _ => Err(format!("unknown command: {} (try `help`)", line.trim())),
+        }
+    }
+
+    /// Types of a type name, types joined with '/' or a species, as of the
+    /// current generation.
+    fn resolve(&self, token: &str) -> Result<Vec<Type>, String> {
+        let types = match self.dex.get(token) {
+            Some(species) => species.types_in(self.generation).ok_or_else(|| {
+                format!(
+                    "{} is not in generation {}",
+                    species.name_in(self.l10n.code()),
+                    self.generation.number()
+                )
+            })?,
+            None => self
+                .dex
+                .resolve(token, self.generation)
+                .ok_or_else(|| format!("unknown type or species: {}", token))?,
+        };
+        match types.iter().find(|ty| !self.chart.has(ty)) {
+            Some(ty) => Err(format!(
+                "{} is not in generation {}",
+                self.label(ty),
+                self.generation.number()
+            )),
+            None => Ok(types),
+        }
+    }
+
+    fn label(&self, ty: &Type) -> &'static str {
+        ty.to_str(&self.l10n)
+    }
+
+    fn labels(&self, types: &[Type]) -> String {
+        types.iter().map(|ty| self.label(ty)).join("/")
+    }
+
+    /// Types by multiplier, strongest first, leaving out neutral ones.
+    fn grouped(&self, rates: &HashMap<Type, f64>) -> String {
+        let mut groups: Vec<(f64, Vec<&str>)> = Vec::new();
+        for ty in self.chart.types() {
+            let rate = rates[ty];
+            if rate == 1.0 {
+                continue;
+            }
+            match groups.iter_mut().find(|(r, _)| *r == rate) {
+                Some((_, types)) => types.push(self.label(ty)),
+                None => groups.push((rate, vec![self.label(ty)])),
+            }
+        }
+        groups.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        groups
+            .iter()
+            .map(|(rate, types)| format!("{}x: {}", rate, types.join(" ")))
+            .join("\n")
+    }
+
+    fn team_types(&self) -> Result<Vec<Vec<Type>>, String> {
+        self.team.iter().map(|token| self.resolve(token)).collect()
+    }
+
+    fn team_list(&self) -> Result<String, String> {
+        if self.team.is_empty() {
+            return Ok("team is empty".to_string());
+        }
+        let types = self.team_types()?;
+        Ok(self
+            .team
+            .iter()
+            .zip(&types)
+            .enumerate()
+            .map(|(i, (token, types))| format!("{}. {} ({})", i + 1, token, self.labels(types)))
+            .join("\n"))
+    }
+
+    fn team_report(&self) -> Result<String, String> {
+        if self.team.is_empty() {
+            return Err("team is empty; add members with `team add`".to_string());
+        }
+        let report = WeaknessReport::new(&self.chart, &self.team_types()?);
+        let mut out = String::from("type      weak  4x  neutral  resist  immune\n");
+        for m in &report.matchups {
+            out += &format!(
+                "{} {:>4} {:>3} {:>8} {:>7} {:>7}\n",
+                pad(self.label(&m.attack_type), 9),
+                m.weak,
+                m.very_weak,
+                m.neutral,
+                m.resist,
+                m.immune
+            );
+        }
+        let stacked = report.stacked(STACKED_WEAKNESS);
+        if !stacked.is_empty() {
+            out += &format!(
+                "stacked weaknesses (>= {}): {}\n",
+                STACKED_WEAKNESS,
+                stacked.iter().map(|m| self.label(&m.attack_type)).join(" ")
+            );
+        }
+        let holes = report.holes();
+        if !holes.is_empty() {
+            out += &format!(
+                "no resist: {}\n",
+                holes.iter().map(|m| self.label(&m.attack_type)).join(" ")
+            );
+        }
+        Ok(out.trim_end().to_string())
+    }
+
+    /// Completions for the word before `pos`, as the byte offset where it
+    /// starts and the candidates. Words after '/' complete too, and
+    /// hiragana matches katakana names.
+    pub fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let before = &line[..pos];
+        let word_start = |separator: &dyn Fn(char) -> bool| {
+            before
+                .char_indices()
+                .rfind(|(_, c)| separator(*c))
+                .map_or(0, |(i, c)| i + c.len_utf8())
+        };
+        let start = word_start(&|c| c.is_whitespace() || c == '/');
+        let previous: Vec<_> = before[..word_start(&char::is_whitespace)]
+            .split_whitespace()
+            .collect();
+
+        let types = || {
+            self.chart
+                .types()
+                .iter()
+                .map(|ty| self.label(ty).to_string())
+        };
+        let candidates: Vec<String> = match previous.as_slice() {
+            [] => COMMANDS.iter().map(|c| c.to_string()).collect(),
+            ["team"] => TEAM_COMMANDS.iter().map(|c| c.to_string()).collect(),
+            ["lang"] => Localization::localizations()
+                .iter()
+                .map(|l10n| l10n.code().to_string())
+                .collect(),
+            ["gen"] => Generation::generations()
+                .iter()
+                .map(|g| g.number().to_string())
+                .collect(),
+            ["atk"] => types().collect(),
+            ["def", ..] | ["team", "add"] => types()
+                .chain(
+                    self.dex
+                        .species()
+                        .iter()
+                        .filter(|species| species.generation <= self.generation)
+                        .map(|species| species.name_in(self.l10n.code())),
+                )
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let prefix = fold(&before[start..]);
+        let mut matches: Vec<_> = candidates
+            .into_iter()
+            .filter(|candidate| fold(candidate).starts_with(&prefix))
+            .collect();
+        matches.sort();
+        matches.dedup();
+        (start, matches)
+    }
+}
+
+/// Lowercases and turns hiragana into katakana for matching.
+fn fold(s: &str) -> String {
+    s.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            '\u{3041}'..='\u{3096}' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+/// Pads to a column width, counting CJK characters as two columns.
+fn pad(s: &str, width: usize) -> String {
+    let used: usize = s.chars().map(|c| if c >= '\u{2e80}' { 2 } else { 1 }).sum();
+    format!("{}{}", s, " ".repeat(width.saturating_sub(used)))
+}
+
+struct ReplHelper<'a> {
+    session: Session<'a>,
+}
+
+impl Completer for ReplHelper<'_> {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.session.complete(line, pos))
+    }
+}
+
+impl Hinter for ReplHelper<'_> {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper<'_> {}
+
+impl Validator for ReplHelper<'_> {}
+
+impl Helper for ReplHelper<'_> {}
+
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".pokemon_type_history"))
+}
+
+/// Reads commands until `quit` or end of input, keeping history in
+/// `~/.pokemon_type_history`.
+pub fn run(dex: &Pokedex, chart: Chart) -> rustyline::Result<()> {
+    let mut editor: Editor<ReplHelper, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(ReplHelper {
+        session: Session::new(dex, chart),
+    }));
+    let history = history_path();
+    if let Some(path) = &history {
+        // A missing history file is normal on the first run.
+        let _ = editor.load_history(path);
+    }
+
+    loop {
+        let line = match editor.readline("> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                // Keep what was entered so far.
+                if let Some(path) = &history {
+                    let _ = editor.save_history(path);
+                }
+                return Err(e);
+            }
+        };
+        if !line.trim().is_empty() {
+            editor.add_history_entry(line.as_str())?;
+        }
+        if matches!(line.trim(), "quit" | "exit") {
+            break;
+        }
+        let session = &mut editor.helper_mut().unwrap().session;
+        match session.execute(&line) {
+            Ok(out) if out.is_empty() => {}
+            Ok(out) => println!("{}", out),
+            Err(message) => eprintln!("{}", message),
+        }
+    }
+
+    if let Some(path) = &history {
+        editor.save_history(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session<'static> {
+        Session::new(Pokedex::bundled(), Chart::builtin())
+    }
+
+    #[test]
+    fn commands() {
+        let mut session = session();
+        assert_eq!(
+            session.execute("def Water Ground").unwrap(),
+            "Water/Ground\n4x: Grass\n0.5x: Poison Rock Steel Fire\n0x: Electric"
+        );
+        assert!(session
+            .execute("atk Ice")
+            .unwrap()
+            .starts_with("2x: Flying Ground Grass Dragon\n0.5x: Steel Fire Water Ice"));
+        assert_eq!(
+            session.execute("team add Steel/Fairy").unwrap(),
+            "1. Steel/Fairy (Steel/Fairy)"
+        );
+        session.execute("team add Garchomp").unwrap();
+        let report = session.execute("team report").unwrap();
+        assert!(report.contains("\nGround       1   0        1       0       0\n"));
+
+        assert_eq!(
+            session.execute("lang ja").unwrap(),
+            "language: ja (types only: species names need a PokeAPI dump, see `--pokeapi <dir>`)"
+        );
+        assert!(session
+            .execute("def 水/地面")
+            .unwrap()
+            .starts_with("水/地面\n4x: 草"));
+        assert_eq!(session.execute("gen 5").unwrap(), "generation 5");
+        assert_eq!(
+            session.execute("team report"),
+            Err("フェアリー is not in generation 5".to_string())
+        );
+        session.execute("team remove 1").unwrap();
+        assert!(session.execute("team report").is_ok());
+        assert!(session.execute("fly").is_err());
+    }
+
+    #[test]
+    fn completion() {
+        let mut session = session();
+        assert_eq!(session.complete("te", 2), (0, vec!["team".to_string()]));
+        assert_eq!(
+            session.complete("team add Steel/Fa", 17),
+            (15, vec!["Fairy".to_string()])
+        );
+        assert!(session
+            .complete("def Garc", 8)
+            .1
+            .contains(&"Garchomp".to_string()));
+
+        session.execute("lang ja").unwrap();
+        let line = "def 水 ふぇ";
+        assert_eq!(
+            session.complete(line, line.len()),
+            (8, vec!["フェアリー".to_string()])
+        );
+        let line = "atk ドラ";
+        assert_eq!(
+            session.complete(line, line.len()),
+            (4, vec!["ドラゴン".to_string()])
+        );
+        let line = "atk どら";
+        assert_eq!(session.complete(line, line.len()).1, vec!["ドラゴン"]);
+    }
+}
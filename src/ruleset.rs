@@ -0,0 +1,219 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// Folds the mainline multipliers of one attacking type against each of a
+/// defender's types (2, 1, 0.5 or 0, or others from custom charts) into
+/// the multiplier a game uses.
+pub trait Stacking: fmt::Debug + Send + Sync {
+    fn stack(&self, relations: &[f64]) -> f64;
+}
+
+/// Stacking that counts super-effective and resisted types and looks the
+/// difference up in a table, as spin-offs with capped multipliers do.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tiered {
+    /// Multipliers for one, two, ... more super-effective than resisted
+    /// types; the last one applies to anything beyond.
+    super_effective: Vec<f64>,
+    /// Multipliers for one, two, ... more resisted than super-effective
+    /// types.
+    resisted: Vec<f64>,
+    /// Multiplier when any type is immune.
+    immune: f64,
+}
+
+impl Tiered {
+    /// Tables for a custom game; `None` unless both have a tier.
+    pub fn new(super_effective: Vec<f64>, resisted: Vec<f64>, immune: f64) -> Option<Self> {
+        if super_effective.is_empty() || resisted.is_empty() {
+            return None;
+        }
+        Some(Self {
+            super_effective,
+            resisted,
+            immune,
+        })
+    }
+}
+
+impl Stacking for Tiered {
+    fn stack(&self, relations: &[f64]) -> f64 {
+        if relations.contains(&0.0) {
+            return self.immune;
+        }
+        let net: f64 = relations.iter().map(|rate| rate.log2()).sum();
+        let tier = |table: &[f64], steps: f64| {
+            let index = (steps.round() as usize).min(table.len()).saturating_sub(1);
+            table[index]
+        };
+        if net >= 0.5 {
+            tier(&self.super_effective, net)
+        } else if net <= -0.5 {
+            tier(&self.resisted, -net)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// How a chart turns type relationships into multipliers. The chart
+/// always stores mainline multipliers; a ruleset gives the numbers a game
+/// actually uses for a defender's types together.
+#[derive(Debug, Clone, Default)]
+pub enum Ruleset {
+    /// Multipliers multiply: 4× for two weak types.
+    #[default]
+    Mainline,
+    /// Legends: Arceus: 2× or 2.5× when weak, 0.5× or 0.4× when resisted.
+    LegendsArceus,
+    /// Mystery Dungeon: 1.4× or 1.8× when weak, 0.7× or 0.5× when
+    /// resisted.
+    MysteryDungeon,
+    /// Pokémon GO: 1.6× super effective, 0.625× resisted and 0.390625×
+    /// (a double resistance) in place of an immunity, multiplied together.
+    Go,
+    /// A user-supplied rule.
+    Custom(Arc<dyn Stacking>),
+}
+
+/// Custom rules are equal only to themselves.
+impl PartialEq for Ruleset {
+    fn eq(&self, other: &Ruleset) -> bool {
+        use Ruleset::*;
+        match (self, other) {
+            (Custom(a), Custom(b)) => Arc::ptr_eq(a, b),
+            (Mainline, Mainline)
+            | (LegendsArceus, LegendsArceus)
+            | (MysteryDungeon, MysteryDungeon)
+            | (Go, Go) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Ruleset {
+    /// The built-in rulesets.
+    pub fn rulesets() -> [Ruleset; 4] {
+        use Ruleset::*;
+        [Mainline, LegendsArceus, MysteryDungeon, Go]
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Ruleset> {
+        Ruleset::rulesets()
+            .iter()
+            .find(|ruleset| ruleset.to_str().eq_ignore_ascii_case(s))
+            .cloned()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        use Ruleset::*;
+        match *self {
+            Mainline => "mainline",
+            LegendsArceus => "legends-arceus",
+            MysteryDungeon => "mystery-dungeon",
+            Go => "go",
+            Custom(_) => "custom",
+        }
+    }
+
+    /// Multiplier against a single type with the given mainline
+    /// multiplier.
+    pub fn multiplier(&self, rate: f64) -> f64 {
+        self.stack(&[rate])
+    }
+}
+
+impl Stacking for Ruleset {
+    fn stack(&self, relations: &[f64]) -> f64 {
+        match self {
+            Ruleset::Mainline => relations.iter().fold(1.0, |a, b| a * b),
+            Ruleset::LegendsArceus => Tiered {
+                super_effective: vec![2.0, 2.5],
+                resisted: vec![0.5, 0.4],
+                immune: 0.0,
+            }
+            .stack(relations),
+            Ruleset::MysteryDungeon => Tiered {
+                super_effective: vec![1.4, 1.8],
+                resisted: vec![0.7, 0.5],
+                immune: 0.0,
+            }
+            .stack(relations),
+            // Steps are summed before raising, so the defender's types
+            // can come in any order.
+            Ruleset::Go => {
+                let (weak, resisted) =
+                    relations.iter().fold((0.0, 0.0), |(weak, resisted), rate| {
+                        if *rate == 0.0 {
+                            (weak, resisted + 2.0)
+                        } else if *rate > 1.0 {
+                            (weak + rate.log2(), resisted)
+                        } else {
+                            (weak, resisted - rate.log2())
+                        }
+                    });
+                1.6f64.powf(weak) * 0.625f64.powf(resisted)
+            }
+            Ruleset::Custom(stacking) => stacking.stack(relations),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn go_multipliers() {
+        let go = Ruleset::Go;
+        assert_eq!(go.multiplier(2.0), 1.6);
+        assert_eq!(go.multiplier(1.0), 1.0);
+        assert_eq!(go.multiplier(0.5), 0.625);
+        assert_eq!(go.multiplier(0.0), 0.390625);
+        assert!((go.stack(&[2.0, 2.0]) - 2.56).abs() < 1e-12);
+        assert_eq!(Ruleset::Mainline.multiplier(0.0), 0.0);
+        assert_eq!(Ruleset::from_str("GO"), Some(Ruleset::Go));
+        // The defender's types may come in any order.
+        assert_eq!(go.stack(&[2.0, 2.0, 0.5]), go.stack(&[0.5, 2.0, 2.0]));
+        assert_eq!(go.stack(&[2.0, 0.5]), 1.0);
+    }
+
+    #[test]
+    fn tiered_rules() {
+        let arceus = Ruleset::LegendsArceus;
+        assert_eq!(arceus.stack(&[2.0]), 2.0);
+        assert_eq!(arceus.stack(&[2.0, 2.0]), 2.5);
+        assert_eq!(arceus.stack(&[2.0, 2.0, 2.0]), 2.5);
+        assert_eq!(arceus.stack(&[0.5, 0.5]), 0.4);
+        assert_eq!(arceus.stack(&[2.0, 0.5]), 1.0);
+        assert_eq!(arceus.stack(&[2.0, 0.0]), 0.0);
+        assert_eq!(arceus.stack(&[]), 1.0);
+
+        let dungeon = Ruleset::MysteryDungeon;
+        assert_eq!(dungeon.stack(&[2.0, 2.0]), 1.8);
+        assert_eq!(dungeon.stack(&[0.5]), 0.7);
+
+        assert_eq!(Tiered::new(vec![], vec![0.5], 0.0), None);
+        let flat = Ruleset::Custom(Arc::new(Tiered::new(vec![3.0], vec![0.25], 0.0).unwrap()));
+        assert_eq!(flat.stack(&[2.0, 2.0]), 3.0);
+        assert_eq!(flat.stack(&[1.5]), 3.0);
+        assert_eq!(flat.stack(&[0.5, 0.5]), 0.25);
+    }
+
+    #[test]
+    fn custom_rule() {
+        #[derive(Debug)]
+        struct Strongest;
+        impl Stacking for Strongest {
+            fn stack(&self, relations: &[f64]) -> f64 {
+                relations.iter().copied().fold(1.0, f64::max)
+            }
+        }
+
+        let custom = Ruleset::Custom(Arc::new(Strongest));
+        assert_eq!(custom.stack(&[2.0, 2.0]), 2.0);
+        assert_eq!(custom, custom.clone());
+        assert_ne!(custom, Ruleset::Custom(Arc::new(Strongest)));
+    }
+}
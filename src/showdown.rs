@@ -0,0 +1,856 @@
+use crate::chart::Chart;
+use crate::generation::Generation;
+use crate::immunity::{type_rules, TypeRule};
+use crate::moves::Movedex;
+use crate::species::Pokedex;
+use crate::stats::{Nature, Stat};
+use crate::team::{Member, Team};
+use crate::types::{ChartType, CustomType, Type};
+use itertools::Itertools;
+use std::error::Error;
+use std::fmt;
+
+/// An error in Showdown formatted data, with the 1-based line it was found
+/// on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Showdown's name for a type, e.g. "Fighting".
+pub fn showdown_name(ty: &Type) -> String {
+    capitalize(ty.identifier())
+}
+
+fn capitalize(id: &str) -> String {
+    let mut chars = id.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// `damageTaken` code for a multiplier.
+fn damage_code(rate: f64) -> u8 {
+    if rate == 0.0 {
+        3
+    } else if rate < 1.0 {
+        2
+    } else if rate > 1.0 {
+        1
+    } else {
+        0
+    }
+}
+
+pub(crate) fn code_rate(code: f64) -> Option<f64> {
+    if code.fract() != 0.0 {
+        return None;
+    }
+    match code as u8 {
+        0 if code == 0.0 => Some(1.0),
+        1 => Some(2.0),
+        2 => Some(0.5),
+        3 => Some(0.0),
+        _ => None,
+    }
+}
+
+/// Showdown keys for the status and weather immunities a type grants in
+/// any of the given generations.
+fn status_keys(ty: &Type, generations: &[Generation]) -> Vec<&'static str> {
+    generations
+        .iter()
+        .flat_map(|generation| type_rules(ty, *generation))
+        .unique()
+        .flat_map(|rule| match rule {
+            TypeRule::BurnImmune => vec!["brn"],
+            TypeRule::ParalysisImmune => vec!["par"],
+            TypeRule::FreezeImmune => vec!["frz"],
+            TypeRule::PoisonImmune => vec!["psn", "tox"],
+            TypeRule::PowderImmune => vec!["powder"],
+            TypeRule::TrappingImmune => vec!["trapped"],
+            TypeRule::PranksterImmune => vec!["prankster"],
+            TypeRule::SandstormImmune => vec!["sandstorm"],
+            TypeRule::HailImmune => vec!["hail"],
+            _ => vec![],
+        })
+        .collect()
+}
+
+/// Generations whose built-in chart has the same types and matchups as
+/// `chart`, custom types aside, or the latest one for any other chart.
+/// Gens 6 to 9 share a chart, so their keys are exported together.
+fn chart_generations<T: ChartType>(chart: &Chart<T>) -> Vec<Generation> {
+    let builtin: Vec<_> = chart
+        .types()
+        .iter()
+        .filter_map(|ty| Some((*ty, ty.builtin()?)))
+        .collect();
+    let matching: Vec<_> = Generation::generations()
+        .iter()
+        .copied()
+        .filter(|generation| {
+            let reference = Chart::generation(*generation);
+            builtin.len() == reference.types().len()
+                && builtin.iter().all(|(a, builtin_a)| {
+                    reference.has(builtin_a)
+                        && builtin.iter().all(|(b, builtin_b)| {
+                            chart.relation(a, b) == reference.relation(builtin_a, builtin_b)
+                        })
+                })
+        })
+        .collect();
+    if matching.is_empty() {
+        vec![Generation::latest()]
+    } else {
+        matching
+    }
+}
+
+/// Whether a `damageTaken` key is a status or weather immunity rather than
+/// an attacking type.
+pub(crate) fn is_status_key(key: &str) -> bool {
+    Type::types()
+        .iter()
+        .any(|ty| status_keys(ty, &Generation::generations()).contains(&key))
+}
+
+/// Entries of the chart in Showdown order: defenders by id, each with its
+/// `damageTaken` codes.
+fn entries<T: ChartType>(chart: &Chart<T>) -> Vec<(String, Vec<(String, u8)>)> {
+    let mut defenders = chart.types().to_vec();
+    defenders.sort_by_key(|ty| chart.identifier(ty));
+    let mut attackers = chart.types().to_vec();
+    attackers.sort_by_key(|ty| capitalize(&chart.identifier(ty)));
+    let generations = chart_generations(chart);
+
+    defenders
+        .iter()
+        .map(|block_type| {
+            let mut taken: Vec<_> = block_type
+                .builtin()
+                .map(|ty| status_keys(&ty, &generations))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|key| (key.to_string(), 3))
+                .collect();
+            taken.extend(attackers.iter().map(|attack_type| {
+                (
+                    capitalize(&chart.identifier(attack_type)),
+                    damage_code(chart.relation(attack_type, block_type)),
+                )
+            }));
+            (chart.identifier(block_type), taken)
+        })
+        .collect()
+}
+
+/// Exports a chart as a Showdown `typechart.ts` module.
+pub fn to_typechart_ts<T: ChartType>(chart: &Chart<T>) -> String {
+    let mut out = String::from("export const TypeChart: {[k: string]: TypeData} = {\n");
+    for (id, taken) in entries(chart) {
+        out += &format!("\t{}: {{\n\t\tdamageTaken: {{\n", ts_key(&id));
+        for (key, code) in taken {
+            out += &format!("\t\t\t{}: {},\n", ts_key(&key), code);
+        }
+        out += "\t\t},\n\t},\n";
+    }
+    out += "};\n";
+    out
+}
+
+/// The key as written in TypeScript, quoted unless it is an identifier.
+fn ts_key(key: &str) -> String {
+    let identifier = key
+        .chars()
+        .enumerate()
+        .all(|(i, c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+    if identifier && !key.is_empty() {
+        key.to_string()
+    } else {
+        format!("'{}'", key.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+/// Exports a chart in Showdown's JSON layout.
+pub fn to_typechart_json<T: ChartType>(chart: &Chart<T>) -> String {
+    let entries: Vec<_> = entries(chart)
+        .into_iter()
+        .map(|(id, taken)| {
+            let taken: Vec<_> = taken
+                .iter()
+                .map(|(key, code)| format!("{}:{}", json_string(key), code))
+                .collect();
+            format!(
+                "{}:{{\"damageTaken\":{{{}}}}}",
+                json_string(&id),
+                taken.join(",")
+            )
+        })
+        .collect();
+    format!("{{{}}}\n", entries.join(","))
+}
+
+/// The string as a quoted JSON string.
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            '\n' => out += "\\n",
+            c if c.is_control() => out += &format!("\\u{:04x}", c as u32),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Imports a Showdown type chart from `typechart.ts`, a mod's typechart or
+/// JSON. Entries marked `inherit: true` start from `base`; if every entry
+/// inherits, the chart keeps all of `base`'s types. Status keys and types
+/// without a `Type` are ignored.
+pub fn from_typechart(text: &str, base: &Chart) -> Result<Chart, ParseError> {
+    let root = parse_typechart(text)?;
+    let entries = typechart_entries(&root)?;
+    let defenders: Vec<_> = entries
+        .iter()
+        .filter_map(|entry| Some((Type::from_identifier(entry.id)?, entry)))
+        .collect();
+
+    let mut chart = if defenders.iter().all(|(_, entry)| entry.inherit) {
+        base.clone()
+    } else {
+        let types: Vec<_> = Type::types()
+            .iter()
+            .filter(|ty| defenders.iter().any(|(d, _)| d == *ty))
+            .copied()
+            .collect();
+        let mut chart = Chart::neutral(&types).with_ruleset(base.ruleset().clone());
+        for (block_type, entry) in &defenders {
+            if entry.inherit {
+                for attack_type in types.iter() {
+                    chart.set(
+                        attack_type,
+                        block_type,
+                        base.relation(attack_type, block_type),
+                    );
+                }
+            }
+        }
+        chart
+    };
+
+    for (block_type, entry) in defenders {
+        for (name, line, code) in &entry.taken {
+            if let Some(attack_type) = Type::from_identifier(name) {
+                chart.set(&attack_type, &block_type, taken_rate(name, *line, code)?);
+            }
+        }
+    }
+    Ok(chart)
+}
+
+/// Like `from_typechart`, but entries that are not built-in types, such as
+/// a fan game's, become custom types of the chart. Attacking types are
+/// matched against the chart's identifiers, so status keys are still
+/// ignored.
+pub fn from_typechart_custom(text: &str, base: &Chart) -> Result<Chart<CustomType>, ParseError> {
+    let root = parse_typechart(text)?;
+    let entries = typechart_entries(&root)?;
+
+    let mut chart = if entries.iter().all(|entry| entry.inherit) {
+        Chart::from_builtin(base)
+    } else {
+        let types: Vec<CustomType> = Type::types()
+            .iter()
+            .filter(|ty| entries.iter().any(|entry| entry.id == ty.identifier()))
+            .map(CustomType::from)
+            .collect();
+        let mut chart = Chart::neutral(&types).with_ruleset(base.ruleset().clone());
+        for entry in entries.iter().filter(|entry| entry.inherit) {
+            if let Some(block_type) = Type::from_identifier(entry.id) {
+                for attack_type in base
+                    .types()
+                    .iter()
+                    .filter(|ty| types.contains(&CustomType::from(**ty)))
+                {
+                    chart.set(
+                        &attack_type.into(),
+                        &block_type.into(),
+                        base.relation(attack_type, &block_type),
+                    );
+                }
+            }
+        }
+        chart
+    };
+    let defenders: Vec<_> = entries
+        .iter()
+        .map(|entry| (chart.add_type(entry.id), entry))
+        .collect();
+    for (ty, entry) in &defenders {
+        if ty.builtin().is_none() {
+            chart.set_name(ty, "en", &capitalize(entry.id));
+        }
+    }
+
+    for (block_type, entry) in defenders {
+        for (name, line, code) in &entry.taken {
+            if let Some(attack_type) = chart.find(name) {
+                chart.set(&attack_type, &block_type, taken_rate(name, *line, code)?);
+            }
+        }
+    }
+    Ok(chart)
+}
+
+/// One type of a Showdown type chart.
+pub(crate) struct TypechartEntry<'a> {
+    pub(crate) id: &'a str,
+    pub(crate) line: usize,
+    pub(crate) inherit: bool,
+    /// `damageTaken` keys with their lines and codes.
+    pub(crate) taken: Vec<(&'a str, usize, &'a Value)>,
+}
+
+pub(crate) fn parse_typechart(text: &str) -> Result<Vec<(String, usize, Value)>, ParseError> {
+    let start = match text.find('=') {
+        Some(eq) if text[..eq].contains("TypeChart") => eq + 1,
+        _ => 0,
+    };
+    match Parser::new(text, start).value()? {
+        Value::Object(entries) => Ok(entries),
+        _ => Err(ParseError::new(1, "expected an object")),
+    }
+}
+
+pub(crate) fn typechart_entries(
+    root: &[(String, usize, Value)],
+) -> Result<Vec<TypechartEntry<'_>>, ParseError> {
+    let mut entries = Vec::new();
+    for (id, line, value) in root {
+        let fields = match value {
+            Value::Object(fields) => fields,
+            _ => return Err(ParseError::new(*line, format!("`{}` is not an object", id))),
+        };
+        let mut entry = TypechartEntry {
+            id,
+            line: *line,
+            inherit: false,
+            taken: Vec::new(),
+        };
+        for (key, line, value) in fields {
+            match (key.as_str(), value) {
+                ("inherit", Value::Bool(inherit)) => entry.inherit = *inherit,
+                ("damageTaken", Value::Object(taken)) => entry.taken.extend(
+                    taken
+                        .iter()
+                        .map(|(name, line, code)| (name.as_str(), *line, code)),
+                ),
+                ("damageTaken", _) => {
+                    return Err(ParseError::new(*line, "`damageTaken` is not an object"))
+                }
+                _ => {}
+            }
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+fn taken_rate(name: &str, line: usize, code: &Value) -> Result<f64, ParseError> {
+    match code {
+        Value::Number(code) => code_rate(*code),
+        _ => None,
+    }
+    .ok_or_else(|| ParseError::new(line, format!("invalid damage code for `{}`", name)))
+}
+
+/// Parses a team in Showdown's export format, as also used by PokePaste.
+/// Members are separated by blank lines; `=== [format] Name ===` headers
+/// and lines Showdown would ignore (Shiny, Happiness, ...) are skipped.
+pub fn parse_team(text: &str, dex: &Pokedex, moves: &Movedex) -> Result<Team, ParseError> {
+    let mut team = Team::default();
+    let mut member: Option<Member> = None;
+    for (i, line) in text.lines().enumerate() {
+        let line_no = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("===") {
+            team.members.extend(member.take());
+            continue;
+        }
+        let current = match member.as_mut() {
+            Some(current) => current,
+            None => {
+                member = Some(parse_name_line(line, line_no, dex)?);
+                continue;
+            }
+        };
+
+        if let Some(names) = line.strip_prefix('-').or_else(|| line.strip_prefix('~')) {
+            for name in names.split(" / ") {
+                let mv = moves.get(name).ok_or_else(|| {
+                    ParseError::new(line_no, format!("unknown move `{}`", name.trim()))
+                })?;
+                current.moves.push(mv);
+            }
+        } else if let Some(nature) = line.strip_suffix(" Nature") {
+            current.spread.nature = Nature::from_str(nature.trim())
+                .ok_or_else(|| ParseError::new(line_no, format!("unknown nature `{}`", nature)))?;
+        } else if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            match key.trim() {
+                "Ability" => current.ability = Some(value.to_string()),
+                "Tera Type" => current.tera = parse_tera(value, line_no)?,
+                "Level" => {
+                    current.spread.level = value
+                        .parse()
+                        .ok()
+                        .filter(|level| (1..=100).contains(level))
+                        .ok_or_else(|| {
+                            ParseError::new(line_no, format!("invalid level `{}`", value))
+                        })?
+                }
+                "EVs" => current.spread.evs = parse_stats(value, [0; 6], line_no)?,
+                "IVs" => current.spread.ivs = parse_stats(value, [31; 6], line_no)?,
+                _ => {}
+            }
+        } else {
+            return Err(ParseError::new(
+                line_no,
+                format!("unexpected line `{}`", line),
+            ));
+        }
+    }
+    team.members.extend(member);
+    Ok(team)
+}
+
+/// Parses `Nickname (Species) (M) @ Item`, where everything but the
+/// species is optional.
+fn parse_name_line(line: &str, line_no: usize, dex: &Pokedex) -> Result<Member, ParseError> {
+    let (name, item) = match line.split_once(" @ ") {
+        Some((name, item)) => (name.trim(), Some(item.trim().to_string())),
+        None => (line, None),
+    };
+    let name = name
+        .strip_suffix(" (M)")
+        .or_else(|| name.strip_suffix(" (F)"))
+        .unwrap_or(name);
+    let (nickname, species) = match name.rfind(" (") {
+        Some(open) if name.ends_with(')') => (
+            Some(name[..open].trim().to_string()),
+            &name[open + 2..name.len() - 1],
+        ),
+        _ => (None, name),
+    };
+    let species = dex
+        .get(species)
+        .ok_or_else(|| ParseError::new(line_no, format!("unknown species `{}`", species)))?;
+    let mut member = Member::new(species.clone());
+    member.nickname = nickname;
+    member.item = item;
+    Ok(member)
+}
+
+/// A Stellar Tera type keeps the original types on defense, so it counts
+/// as no Tera type.
+fn parse_tera(value: &str, line_no: usize) -> Result<Option<Type>, ParseError> {
+    if value.eq_ignore_ascii_case("stellar") {
+        return Ok(None);
+    }
+    Type::from_identifier(value)
+        .or_else(|| Type::from_str(value))
+        .map(Some)
+        .ok_or_else(|| ParseError::new(line_no, format!("unknown type `{}`", value)))
+}
+
+/// Parses `252 Atk / 4 SpD / 252 Spe`; unlisted stats keep `default`.
+fn parse_stats(value: &str, default: [u32; 6], line_no: usize) -> Result<[u32; 6], ParseError> {
+    let mut stats = default;
+    for part in value.split('/') {
+        let invalid = || ParseError::new(line_no, format!("invalid stat `{}`", part.trim()));
+        let (amount, stat) = part.trim().split_once(' ').ok_or_else(invalid)?;
+        let stat = Stat::from_str(stat.trim()).ok_or_else(invalid)?;
+        stats[stat.index()] = amount.parse().map_err(|_| invalid())?;
+    }
+    Ok(stats)
+}
+
+/// The subset of JavaScript object literals used by Showdown data files.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Object(Vec<(String, usize, Value)>),
+    Array(Vec<Value>),
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Null,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn new(text: &str, start: usize) -> Self {
+        Self {
+            chars: text[start..].chars().collect(),
+            pos: 0,
+            line: text[..start].matches('\n').count() + 1,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.line, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_space(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') if self.chars.get(self.pos + 1) == Some(&'/') => {
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        self.bump();
+                    }
+                }
+                Some('/') if self.chars.get(self.pos + 1) == Some(&'*') => {
+                    self.bump();
+                    self.bump();
+                    while self.peek().is_some()
+                        && !(self.peek() == Some('*') && self.chars.get(self.pos + 1) == Some(&'/'))
+                    {
+                        self.bump();
+                    }
+                    self.bump();
+                    self.bump();
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        self.skip_space();
+        match self.bump() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(self.error(format!("expected `{}`, found `{}`", expected, c))),
+            None => Err(self.error(format!("expected `{}`, found end of input", expected))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        self.skip_space();
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') | Some('\'') => Ok(Value::String(self.string()?)),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(_) => match self.word().as_str() {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                "null" | "undefined" => Ok(Value::Null),
+                "" => Err(self.error(format!("unexpected `{}`", self.peek().unwrap()))),
+                word => Err(self.error(format!("unexpected `{}`", word))),
+            },
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, ParseError> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        loop {
+            self.skip_space();
+            if self.peek() == Some('}') {
+                self.bump();
+                return Ok(Value::Object(fields));
+            }
+            let line = self.line;
+            let key = match self.peek() {
+                Some('"') | Some('\'') => self.string()?,
+                _ => self.word(),
+            };
+            if key.is_empty() {
+                return Err(self.error("expected a key"));
+            }
+            self.expect(':')?;
+            let value = self.value()?;
+            fields.push((key, line, value));
+            self.skip_space();
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some('}') => {}
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, ParseError> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+        loop {
+            self.skip_space();
+            if self.peek() == Some(']') {
+                self.bump();
+                return Ok(Value::Array(values));
+            }
+            values.push(self.value()?);
+            self.skip_space();
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(']') => {}
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let quote = self.bump().unwrap();
+        let mut s = String::new();
+        loop {
+            match self.bump() {
+                Some('\\') => match self.bump() {
+                    Some('n') => s.push('\n'),
+                    Some(c) => s.push(c),
+                    None => break,
+                },
+                Some(c) if c == quote => return Ok(s),
+                Some(c) => s.push(c),
+                None => break,
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn number(&mut self) -> Result<Value, ParseError> {
+        let mut s = String::new();
+        while let Some(c) = self.peek() {
+            if c == '-' || c == '.' || c == 'e' || c == 'E' || c == '+' || c.is_ascii_digit() {
+                s.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        s.parse()
+            .map(Value::Number)
+            .map_err(|_| self.error(format!("invalid number `{}`", s)))
+    }
+
+    fn word(&mut self) -> String {
+        let mut s = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || c == '_' || c == '$' {
+                s.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+
+    #[test]
+    fn round_trip_ts() {
+        let chart = Chart::builtin();
+        let ts = to_typechart_ts(&chart);
+        assert!(ts.contains("\tfighting: {"));
+        assert!(ts.contains("\t\t\tbrn: 3,"));
+        assert_eq!(from_typechart(&ts, &Chart::neutral(&[])).unwrap(), chart);
+    }
+
+    #[test]
+    fn status_keys_follow_generation() {
+        let ice = |generation| {
+            let ts = to_typechart_ts(&Chart::generation(generation));
+            let start = ts.find("\tice: {").unwrap();
+            ts[start..start + ts[start..].find("\t},").unwrap()].to_string()
+        };
+        assert!(ice(Generation::Gen5).contains("\t\t\thail: 3,"));
+        assert!(ice(Generation::Gen8).contains("\t\t\thail: 3,"));
+        assert!(!ice(Generation::Gen1).contains("hail"));
+        assert!(ice(Generation::Gen1).contains("\t\t\tfrz: 3,"));
+    }
+
+    #[test]
+    fn round_trip_json() {
+        let chart = Chart::generation(Generation::Gen5);
+        let json = to_typechart_json(&chart);
+        assert_eq!(from_typechart(&json, &Chart::builtin()).unwrap(), chart);
+
+        let mut custom = Chart::from_builtin(&Chart::neutral(&[Fire]));
+        let quoted = custom.add_type("sha\"dow\\");
+        custom.set(&quoted, &quoted, 0.5);
+        let json = to_typechart_json(&custom);
+        assert!(json.contains("\"sha\\\"dow\\\\\":{\"damageTaken\":{"));
+        assert!(json.contains("\"Sha\\\"dow\\\\\":2"));
+        assert_eq!(
+            from_typechart_custom(&json, &Chart::builtin()).unwrap(),
+            custom
+        );
+    }
+
+    #[test]
+    fn mod_chart_inherits() {
+        let text = "export const TypeChart: ModdedTypeDataTable = {\n\
+                    \tsteel: {\n\
+                    \t\tinherit: true,\n\
+                    \t\tdamageTaken: {\n\
+                    \t\t\tGhost: 2, // resisted again\n\
+                    \t\t\tDark: 2,\n\
+                    \t\t},\n\
+                    \t},\n\
+                    };\n";
+        let chart = from_typechart(text, &Chart::builtin()).unwrap();
+        assert_eq!(chart.combat1(&Ghost, &Steel), 0.5);
+        assert_eq!(chart.combat1(&Fire, &Steel), 2.0);
+        assert_eq!(chart.types().len(), 18);
+    }
+
+    #[test]
+    fn custom_types() {
+        let text = "{\n  fire: {damageTaken: {Fire: 2, Water: 1, Shadow: 1}},\n  \
+                    shadow: {damageTaken: {brn: 3, Fire: 0, Shadow: 2}},\n}";
+        let chart = from_typechart_custom(text, &Chart::builtin()).unwrap();
+        assert_eq!(chart.types().len(), 2);
+        let fire = chart.find("fire").unwrap();
+        let shadow = chart.find("shadow").unwrap();
+        assert_eq!(fire.builtin(), Some(Fire));
+        assert_eq!(shadow.builtin(), None);
+        assert_eq!(chart.name(&shadow, "ja"), "Shadow");
+        assert_eq!(chart.combat1(&shadow, &fire), 2.0);
+        assert_eq!(chart.combat1(&shadow, &shadow), 0.5);
+        assert_eq!(chart.combat1(&fire, &fire), 0.5);
+
+        let round_trip = from_typechart_custom(&to_typechart_ts(&chart), &Chart::builtin());
+        assert_eq!(round_trip.unwrap(), chart);
+    }
+
+    #[test]
+    fn errors_have_lines() {
+        let text = "{\n  fire: {\n    damageTaken: {\n      Water: 7,\n    },\n  },\n}";
+        let error = from_typechart(text, &Chart::builtin()).unwrap_err();
+        assert_eq!(error.line, 4);
+        // Fractional codes must not be truncated to a valid one.
+        for code in ["1.5", "0.5", "2.9"].iter() {
+            let text = text.replace("Water: 7", &format!("Water: {}", code));
+            assert_eq!(
+                from_typechart(&text, &Chart::builtin()).unwrap_err().line,
+                4
+            );
+        }
+
+        let error =
+            from_typechart("{\n  fire: {\n    damageTaken: [\n", &Chart::builtin()).unwrap_err();
+        assert_eq!(error.line, 4);
+    }
+
+    const PASTE: &str = "=== [gen9ou] Sample ===
+
+Chompy (Garchomp) (M) @ Choice Scarf
+Ability: Rough Skin
+Tera Type: Steel
+EVs: 252 Atk / 4 SpD / 252 Spe
+Jolly Nature
+- Earthquake
+- Outrage
+- Stone Edge
+- Tera Blast
+
+Rotom-Wash @ Leftovers
+Ability: Levitate
+Level: 50
+Shiny: Yes
+Tera Type: Stellar
+EVs: 252 HP / 252 Def / 4 SpD
+Bold Nature
+IVs: 0 Atk
+- Hydro Pump
+- Volt Switch
+- Will-O-Wisp
+- Hidden Power [Ice]
+";
+
+    #[test]
+    fn parse_paste() {
+        let team = parse_team(PASTE, Pokedex::bundled(), Movedex::bundled()).unwrap();
+        assert_eq!(team.members.len(), 2);
+
+        let chomp = &team.members[0];
+        assert_eq!(chomp.name(), "Chompy");
+        assert_eq!(chomp.species.identifier, "garchomp");
+        assert_eq!(chomp.item.as_deref(), Some("Choice Scarf"));
+        assert_eq!(chomp.ability.as_deref(), Some("Rough Skin"));
+        assert_eq!(chomp.tera, Some(Steel));
+        assert_eq!(chomp.spread.nature, Nature::Jolly);
+        assert_eq!(chomp.spread.stats(), [357, 359, 226, 176, 207, 333]);
+        assert_eq!(chomp.attack_types(), vec![Ground, Dragon, Rock, Steel]);
+
+        let rotom = &team.members[1];
+        assert_eq!(rotom.types(), &[Electric, Water]);
+        assert_eq!(rotom.tera, None);
+        assert_eq!(rotom.spread.level, 50);
+        assert_eq!(rotom.spread.ivs, [31, 0, 31, 31, 31, 31]);
+        assert_eq!(rotom.attack_types(), vec![Water, Electric, Ice]);
+    }
+
+    #[test]
+    fn paste_errors_have_lines() {
+        let parse = |text| parse_team(text, Pokedex::bundled(), Movedex::bundled());
+        let error = parse("Garchomp\n- Earthquake\n- Splash\n").unwrap_err();
+        assert_eq!(error, ParseError::new(3, "unknown move `Splash`"));
+        let error = parse("Garchomp\n\nMissingno @ Leftovers\n").unwrap_err();
+        assert_eq!(error.line, 3);
+        let error = parse("Garchomp\nEVs: 252 Atk / lots Spe\n").unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+}
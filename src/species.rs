@@ -0,0 +1,622 @@
+use crate::csv::{parse_number, Table};
+use crate::generation::Generation;
+use crate::pokeapi::{language_code, pokeapi_type, read_id_map};
+use crate::types::{Localization, Type};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+/// A Pokémon species or one of its forms (regional forms, Megas, Rotom
+/// appliances, Ogerpon masks, ...).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Species {
+    /// PokeAPI-style identifier, e.g. "rotom-wash".
+    pub identifier: String,
+    /// Identifier of the species this form belongs to, e.g. "rotom".
+    pub species: String,
+    /// Generation the form was introduced in.
+    pub generation: Generation,
+    pub types: Vec<Type>,
+    /// Earlier typings, each valid up to and including its generation.
+    pub past_types: Vec<(Generation, Vec<Type>)>,
+    pub base_stats: [u32; 6],
+    pub abilities: Vec<String>,
+    /// Species names by language code, e.g. "ja", for base forms loaded
+    /// from PokeAPI.
+    pub names: HashMap<String, String>,
+}
+
+impl Species {
+    /// Display name such as "Rotom-Wash".
+    pub fn name(&self) -> String {
+        display_name(&self.identifier)
+    }
+
+    /// Name in the given language, or the display name if it is unknown.
+    pub fn name_in(&self, language: &str) -> String {
+        self.names
+            .get(language)
+            .cloned()
+            .unwrap_or_else(|| self.name())
+    }
+
+    /// Form name, e.g. "wash" for "rotom-wash", or `None` for a base form.
+    pub fn form(&self) -> Option<&str> {
+        self.identifier
+            .strip_prefix(&self.species)
+            .and_then(|form| form.strip_prefix('-'))
+    }
+
+    /// Types in the given generation, or `None` if the form doesn't exist
+    /// yet.
+    pub fn types_in(&self, generation: Generation) -> Option<Vec<Type>> {
+        if generation < self.generation {
+            return None;
+        }
+        let past = self
+            .past_types
+            .iter()
+            .filter(|(until, _)| generation <= *until)
+            .min_by_key(|(until, _)| *until);
+        Some(match past {
+            Some((_, types)) => types.clone(),
+            None => self.types.clone(),
+        })
+    }
+}
+
+/// A table of species, either bundled with the crate or loaded from a
+/// PokeAPI CSV dump.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pokedex {
+    species: Vec<Species>,
+}
+
+impl Pokedex {
+    pub fn new(species: Vec<Species>) -> Self {
+        Self { species }
+    }
+
+    /// The species table bundled with the crate. It covers commonly used
+    /// species and forms; load a PokeAPI dump for the full National Dex.
+    pub fn bundled() -> &'static Pokedex {
+        &BUNDLED
+    }
+
+    pub fn species(&self) -> &[Species] {
+        &self.species
+    }
+
+    /// Looks up a species by name, ignoring case, spaces and punctuation,
+    /// or by a localized name such as "ガブリアス". A name that only
+    /// prefixes one form, such as "Ogerpon-Wellspring", finds that form.
+    pub fn get(&self, name: &str) -> Option<&Species> {
+        let id = normalize(name);
+        if let Some(species) = self.species.iter().find(|s| s.identifier == id) {
+            return Some(species);
+        }
+        if let Some(species) = self
+            .species
+            .iter()
+            .find(|s| s.names.values().any(|n| n == name.trim()))
+        {
+            return Some(species);
+        }
+        let prefix = format!("{}-", id);
+        let mut forms = self
+            .species
+            .iter()
+            .filter(|s| s.identifier.starts_with(&prefix));
+        match (forms.next(), forms.next()) {
+            (Some(species), None) => Some(species),
+            _ => None,
+        }
+    }
+
+    /// Species with exactly the given typing, in any order, in the given
+    /// generation.
+    pub fn with_types(&self, types: &[&Type], generation: Generation) -> Vec<&Species> {
+        let mut wanted: Vec<_> = types.iter().map(|ty| **ty).collect();
+        wanted.sort_by_key(|ty| *ty as usize);
+        wanted.dedup();
+        self.species
+            .iter()
+            .filter(|species| match species.types_in(generation) {
+                Some(mut types) => {
+                    types.sort_by_key(|ty| *ty as usize);
+                    types == wanted
+                }
+                None => false,
+            })
+            .collect()
+    }
+
+    /// Resolves a command line token to types. Accepts a type name in any
+    /// language, types joined with '/' like "Water/Ground", or a species
+    /// name, which has its typing of `generation`.
+    pub fn resolve(&self, token: &str, generation: Generation) -> Option<Vec<Type>> {
+        let types: Option<Vec<_>> = token.split('/').map(parse_type).collect();
+        if let Some(types) = types {
+            return Some(types);
+        }
+        self.get(token)?.types_in(generation)
+    }
+
+    /// Loads species from a directory holding the PokeAPI CSV files
+    /// `pokemon.csv`, `pokemon_species.csv`, `pokemon_types.csv`,
+    /// `pokemon_stats.csv`, `pokemon_abilities.csv` and `abilities.csv`.
+    /// `pokemon_types_past.csv`, `pokemon_forms.csv`,
+    /// `version_groups.csv`, `pokemon_species_names.csv` and
+    /// `languages.csv` are used when present.
+    pub fn from_pokeapi(dir: &Path) -> io::Result<Pokedex> {
+        let pokemon = Table::read(&dir.join("pokemon.csv"))?;
+        let species_table = Table::read(&dir.join("pokemon_species.csv"))?;
+
+        let mut species_by_id = HashMap::new();
+        {
+            let id = species_table.column("id")?;
+            let identifier = species_table.column("identifier")?;
+            let generation = species_table.column("generation_id")?;
+            for row in &species_table.rows {
+                species_by_id.insert(
+                    row[id].clone(),
+                    (row[identifier].clone(), parse_generation(&row[generation])?),
+                );
+            }
+        }
+
+        let form_generations = read_form_generations(dir)?;
+        let types = read_types(&dir.join("pokemon_types.csv"), false)?;
+        let past_types = if dir.join("pokemon_types_past.csv").exists() {
+            read_types(&dir.join("pokemon_types_past.csv"), true)?
+        } else {
+            HashMap::new()
+        };
+        let stats = read_stats(&dir.join("pokemon_stats.csv"))?;
+        let abilities = read_abilities(dir)?;
+        let names = read_species_names(dir)?;
+
+        let id = pokemon.column("id")?;
+        let identifier = pokemon.column("identifier")?;
+        let species_id = pokemon.column("species_id")?;
+        let mut species = Vec::new();
+        for row in &pokemon.rows {
+            let (species_identifier, species_generation) = match species_by_id.get(&row[species_id])
+            {
+                Some(species) => species.clone(),
+                None => continue,
+            };
+            let form_types = match types.get(&row[id]) {
+                Some(types) => types.get(&0).cloned().unwrap_or_default(),
+                None => continue,
+            };
+            if form_types.is_empty() {
+                continue;
+            }
+            let mut past: Vec<_> = past_types
+                .get(&row[id])
+                .map(|past| {
+                    past.iter()
+                        .filter_map(|(gen, types)| {
+                            Generation::from_number(*gen).map(|gen| (gen, types.clone()))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            past.sort_by_key(|(gen, _)| *gen);
+
+            let localized = if row[identifier] == species_identifier {
+                names.get(&row[species_id]).cloned().unwrap_or_default()
+            } else {
+                HashMap::new()
+            };
+            species.push(Species {
+                identifier: row[identifier].clone(),
+                species: species_identifier,
+                generation: form_generations
+                    .get(&row[id])
+                    .copied()
+                    .unwrap_or(species_generation)
+                    .max(species_generation),
+                types: form_types,
+                past_types: past,
+                base_stats: stats.get(&row[id]).copied().unwrap_or_default(),
+                abilities: abilities.get(&row[id]).cloned().unwrap_or_default(),
+                names: localized,
+            });
+        }
+        Ok(Pokedex { species })
+    }
+
+    fn from_bundled_csv(text: &str) -> io::Result<Pokedex> {
+        let table = Table::parse(text).ok_or_else(|| invalid_data("empty species table".into()))?;
+        let identifier = table.column("identifier")?;
+        let species = table.column("species")?;
+        let generation = table.column("generation")?;
+        let types = table.column("types")?;
+        let past_types = table.column("past_types")?;
+        let abilities = table.column("abilities")?;
+        let hp = table.column("hp")?;
+
+        let mut result = Vec::new();
+        for row in &table.rows {
+            let mut base_stats = [0; 6];
+            for (i, stat) in base_stats.iter_mut().enumerate() {
+                *stat = parse_number(&row[hp + i])?;
+            }
+            let mut past = Vec::new();
+            for entry in row[past_types].split(';').filter(|e| !e.is_empty()) {
+                let (gen, types) = entry.split_once(':').unwrap_or((entry, ""));
+                past.push((parse_generation(gen)?, parse_identifiers(types)?));
+            }
+            result.push(Species {
+                identifier: row[identifier].clone(),
+                species: row[species].clone(),
+                generation: parse_generation(&row[generation])?,
+                types: parse_identifiers(&row[types])?,
+                past_types: past,
+                base_stats,
+                abilities: row[abilities]
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect(),
+                names: HashMap::new(),
+            });
+        }
+        Ok(Pokedex { species: result })
+    }
+}
+
+/// Normalizes a species name to its PokeAPI identifier form, e.g.
+/// "Mr. Mime" to "mr-mime".
+pub fn normalize(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .replace(['_', ' '], "-")
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '-')
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Title-cases an identifier, e.g. "rotom-wash" to "Rotom-Wash".
+pub(crate) fn display_name(identifier: &str) -> String {
+    identifier
+        .split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(c) => c.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+/// Parses a type by its English name, in either the crate's spelling or
+/// the lowercase data file identifier.
+fn parse_type(s: &str) -> Option<Type> {
+    Type::from_identifier(s).or_else(|| {
+        Localization::localizations()
+            .iter()
+            .find_map(|l10n| Type::from_localized(s, l10n))
+    })
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn parse_generation(s: &str) -> io::Result<Generation> {
+    Generation::from_number(parse_number(s)?)
+        .ok_or_else(|| invalid_data(format!("invalid generation `{}`", s)))
+}
+
+fn parse_identifiers(s: &str) -> io::Result<Vec<Type>> {
+    s.split_whitespace()
+        .map(|id| {
+            Type::from_identifier(id).ok_or_else(|| invalid_data(format!("unknown type `{}`", id)))
+        })
+        .collect()
+}
+
+/// Types per Pokémon id, keyed by generation for past types (0 for current
+/// types).
+fn read_types(path: &Path, past: bool) -> io::Result<HashMap<String, HashMap<u8, Vec<Type>>>> {
+    let table = Table::read(path)?;
+    let pokemon_id = table.column("pokemon_id")?;
+    let type_id = table.column("type_id")?;
+    let slot = table.column("slot")?;
+    let generation = if past {
+        Some(table.column("generation_id")?)
+    } else {
+        None
+    };
+
+    let mut slots: HashMap<String, HashMap<u8, Vec<(u32, Type)>>> = HashMap::new();
+    for row in &table.rows {
+        let gen = match generation {
+            Some(column) => parse_number(&row[column])?,
+            None => 0,
+        };
+        if let Some(ty) = pokeapi_type(&row[type_id]) {
+            slots
+                .entry(row[pokemon_id].clone())
+                .or_default()
+                .entry(gen)
+                .or_default()
+                .push((parse_number(&row[slot])?, ty));
+        }
+    }
+    Ok(slots
+        .into_iter()
+        .map(|(id, gens)| {
+            let gens = gens
+                .into_iter()
+                .map(|(gen, mut types)| {
+                    types.sort_by_key(|(slot, _)| *slot);
+                    (gen, types.into_iter().map(|(_, ty)| ty).collect())
+                })
+                .collect();
+            (id, gens)
+        })
+        .collect())
+}
+
+fn read_stats(path: &Path) -> io::Result<HashMap<String, [u32; 6]>> {
+    let table = Table::read(path)?;
+    let pokemon_id = table.column("pokemon_id")?;
+    let stat_id = table.column("stat_id")?;
+    let base_stat = table.column("base_stat")?;
+
+    let mut stats: HashMap<String, [u32; 6]> = HashMap::new();
+    for row in &table.rows {
+        let stat: usize = parse_number(&row[stat_id])?;
+        if (1..=6).contains(&stat) {
+            stats.entry(row[pokemon_id].clone()).or_default()[stat - 1] =
+                parse_number(&row[base_stat])?;
+        }
+    }
+    Ok(stats)
+}
+
+fn read_abilities(dir: &Path) -> io::Result<HashMap<String, Vec<String>>> {
+    let names = Table::read(&dir.join("abilities.csv"))?;
+    let id = names.column("id")?;
+    let identifier = names.column("identifier")?;
+    let names: HashMap<_, _> = names
+        .rows
+        .iter()
+        .map(|row| (row[id].clone(), row[identifier].clone()))
+        .collect();
+
+    let table = Table::read(&dir.join("pokemon_abilities.csv"))?;
+    let pokemon_id = table.column("pokemon_id")?;
+    let ability_id = table.column("ability_id")?;
+    let slot = table.column("slot")?;
+    let mut abilities: HashMap<String, Vec<(u32, String)>> = HashMap::new();
+    for row in &table.rows {
+        if let Some(name) = names.get(&row[ability_id]) {
+            abilities
+                .entry(row[pokemon_id].clone())
+                .or_default()
+                .push((parse_number(&row[slot])?, name.clone()));
+        }
+    }
+    Ok(abilities
+        .into_iter()
+        .map(|(id, mut abilities)| {
+            abilities.sort();
+            (id, abilities.into_iter().map(|(_, name)| name).collect())
+        })
+        .collect())
+}
+
+/// Generation each Pokémon id's default form was introduced in, when the
+/// form tables are available.
+fn read_form_generations(dir: &Path) -> io::Result<HashMap<String, Generation>> {
+    let (forms, groups) = (
+        dir.join("pokemon_forms.csv"),
+        dir.join("version_groups.csv"),
+    );
+    if !forms.exists() || !groups.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let groups = Table::read(&groups)?;
+    let id = groups.column("id")?;
+    let generation = groups.column("generation_id")?;
+    let mut group_generations = HashMap::new();
+    for row in &groups.rows {
+        group_generations.insert(row[id].clone(), parse_generation(&row[generation])?);
+    }
+
+    let forms = Table::read(&forms)?;
+    let pokemon_id = forms.column("pokemon_id")?;
+    let group = forms.column("introduced_in_version_group_id")?;
+    let is_default = forms.column("is_default")?;
+    Ok(forms
+        .rows
+        .iter()
+        .filter(|row| row[is_default] == "1")
+        .filter_map(|row| {
+            group_generations
+                .get(&row[group])
+                .map(|gen| (row[pokemon_id].clone(), *gen))
+        })
+        .collect())
+}
+
+/// Species names by species id and language code. Languages such as
+/// "ja-Hrkt" are stored under their `Localization` code, the first name
+/// read winning.
+fn read_species_names(dir: &Path) -> io::Result<HashMap<String, HashMap<String, String>>> {
+    let path = dir.join("pokemon_species_names.csv");
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let languages = read_id_map(&dir.join("languages.csv"))?;
+    let table = Table::read(&path)?;
+    let (species_id, language, name) = (
+        table.column("pokemon_species_id")?,
+        table.column("local_language_id")?,
+        table.column("name")?,
+    );
+    let mut names: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for row in &table.rows {
+        names
+            .entry(row[species_id].clone())
+            .or_default()
+            .entry(language_code(&languages, &row[language]))
+            .or_insert_with(|| row[name].clone());
+    }
+    Ok(names)
+}
+
+lazy_static! {
+    static ref BUNDLED: Pokedex = Pokedex::from_bundled_csv(include_str!("species.csv")).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+
+    #[test]
+    fn lookup() {
+        let dex = Pokedex::bundled();
+        assert_eq!(dex.get("Gyarados").unwrap().types, vec![Water, Flying]);
+        assert_eq!(dex.get("Rotom-Wash").unwrap().types, vec![Electric, Water]);
+        assert_eq!(dex.get("rotom wash").unwrap().form(), Some("wash"));
+        assert_eq!(
+            dex.get("Ogerpon-Wellspring").unwrap().types,
+            vec![Grass, Water]
+        );
+        assert_eq!(dex.get("Mr. Mime").unwrap().identifier, "mr-mime");
+        assert_eq!(dex.get("Rotom").unwrap().form(), None);
+        assert_eq!(dex.get("Missingno"), None);
+    }
+
+    #[test]
+    fn types_by_generation() {
+        let dex = Pokedex::bundled();
+        let clefable = dex.get("Clefable").unwrap();
+        assert_eq!(clefable.types_in(Generation::Gen5), Some(vec![Normal]));
+        assert_eq!(clefable.types_in(Generation::Gen6), Some(vec![Fairy]));
+        let magnemite = dex.get("Magnemite").unwrap();
+        assert_eq!(magnemite.types_in(Generation::Gen1), Some(vec![Electric]));
+        assert_eq!(
+            magnemite.types_in(Generation::Gen2),
+            Some(vec![Electric, Steel])
+        );
+        let garchomp = dex.get("Garchomp").unwrap();
+        assert_eq!(garchomp.types_in(Generation::Gen3), None);
+    }
+
+    #[test]
+    fn species_with_types() {
+        let dex = Pokedex::bundled();
+        let names: Vec<_> = dex
+            .with_types(&[&Flying, &Water], Generation::Gen9)
+            .iter()
+            .map(|s| s.name())
+            .collect();
+        assert_eq!(names, vec!["Gyarados"]);
+        assert!(dex.with_types(&[&Fairy], Generation::Gen5).is_empty());
+    }
+
+    #[test]
+    fn resolve_tokens() {
+        let dex = Pokedex::bundled();
+        let latest = Generation::latest();
+        assert_eq!(dex.resolve("Water", latest), Some(vec![Water]));
+        assert_eq!(
+            dex.resolve("fighting/steel", latest),
+            Some(vec![Fight, Steel])
+        );
+        assert_eq!(dex.resolve("水/地面", latest), Some(vec![Water, Ground]));
+        assert_eq!(dex.resolve("Garchomp", latest), Some(vec![Dragon, Ground]));
+        assert_eq!(dex.resolve("Nothing", latest), None);
+        assert_eq!(dex.resolve("Clefable", latest), Some(vec![Fairy]));
+        assert_eq!(
+            dex.resolve("Clefable", Generation::Gen5),
+            Some(vec![Normal])
+        );
+        assert_eq!(dex.resolve("Garchomp", Generation::Gen3), None);
+    }
+
+    #[test]
+    fn load_pokeapi_dump() {
+        let dir = std::env::temp_dir().join(format!("pokemon_type_dex_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let files = [
+            (
+                "pokemon.csv",
+                "id,identifier,species_id,height,weight,base_experience,order,is_default\n\
+                 35,clefairy,35,6,75,113,1,1\n\
+                 10008,rotom-heat,479,3,3,182,1,0\n",
+            ),
+            (
+                "pokemon_species.csv",
+                "id,identifier,generation_id\n35,clefairy,1\n479,rotom,4\n",
+            ),
+            (
+                "pokemon_types.csv",
+                "pokemon_id,type_id,slot\n35,18,1\n10008,13,1\n10008,10,2\n",
+            ),
+            (
+                "pokemon_types_past.csv",
+                "pokemon_id,generation_id,type_id,slot\n35,5,1,1\n10008,4,13,1\n10008,4,8,2\n",
+            ),
+            (
+                "pokemon_stats.csv",
+                "pokemon_id,stat_id,base_stat,effort\n\
+                 35,1,70,2\n35,2,45,0\n35,3,48,0\n35,4,60,0\n35,5,65,0\n35,6,35,0\n",
+            ),
+            (
+                "pokemon_abilities.csv",
+                "pokemon_id,ability_id,is_hidden,slot\n35,98,0,2\n35,56,0,1\n10008,26,0,1\n",
+            ),
+            (
+                "abilities.csv",
+                "id,identifier,generation_id,is_main_series\n26,levitate,3,1\n56,cute-charm,3,1\n98,magic-guard,4,1\n",
+            ),
+            (
+                "languages.csv",
+                "id,iso639,iso3166,identifier,official,order\n1,ja,jp,ja-Hrkt,1,1\n9,en,us,en,1,7\n",
+            ),
+            (
+                "pokemon_species_names.csv",
+                "pokemon_species_id,local_language_id,name,genus\n\
+                 35,1,ピッピ,ようせいポケモン\n35,9,Clefairy,Fairy Pokémon\n479,1,ロトム,プラズマポケモン\n",
+            ),
+        ];
+        for (name, text) in files.iter() {
+            std::fs::write(dir.join(name), text).unwrap();
+        }
+
+        let dex = Pokedex::from_pokeapi(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let clefairy = dex.get("clefairy").unwrap();
+        assert_eq!(clefairy.types_in(Generation::Gen5), Some(vec![Normal]));
+        assert_eq!(clefairy.types, vec![Fairy]);
+        assert_eq!(clefairy.base_stats, [70, 45, 48, 60, 65, 35]);
+        assert_eq!(clefairy.abilities, vec!["cute-charm", "magic-guard"]);
+        assert_eq!(clefairy.name_in("ja"), "ピッピ");
+        assert_eq!(dex.get("ピッピ"), Some(clefairy));
+        let rotom = dex.get("Rotom-Heat").unwrap();
+        assert_eq!(rotom.name_in("ja"), "Rotom-Heat");
+        assert_eq!(rotom.generation, Generation::Gen4);
+        assert_eq!(
+            rotom.types_in(Generation::Gen4),
+            Some(vec![Electric, Ghost])
+        );
+        assert_eq!(rotom.types_in(Generation::Gen5), Some(vec![Electric, Fire]));
+    }
+}
@@ -0,0 +1,236 @@
+const STAT_COUNT: usize = 6;
+
+const NATURE_GRID: [Stat; 5] = [
+    Stat::Attack,
+    Stat::Defense,
+    Stat::Speed,
+    Stat::SpAttack,
+    Stat::SpDefense,
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Stat {
+    Hp,
+    Attack,
+    Defense,
+    SpAttack,
+    SpDefense,
+    Speed,
+}
+
+impl Stat {
+    pub fn stats() -> [Stat; STAT_COUNT] {
+        use Stat::*;
+        [Hp, Attack, Defense, SpAttack, SpDefense, Speed]
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// Abbreviation used by Showdown exports, e.g. "SpA".
+    pub fn to_str(&self) -> &'static str {
+        use Stat::*;
+        match *self {
+            Hp => "HP",
+            Attack => "Atk",
+            Defense => "Def",
+            SpAttack => "SpA",
+            SpDefense => "SpD",
+            Speed => "Spe",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Stat> {
+        Stat::stats()
+            .iter()
+            .find(|stat| stat.to_str().eq_ignore_ascii_case(s))
+            .copied()
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Nature {
+    #[default]
+    Hardy,
+    Lonely,
+    Brave,
+    Adamant,
+    Naughty,
+    Bold,
+    Docile,
+    Relaxed,
+    Impish,
+    Lax,
+    Timid,
+    Hasty,
+    Serious,
+    Jolly,
+    Naive,
+    Modest,
+    Mild,
+    Quiet,
+    Bashful,
+    Rash,
+    Calm,
+    Gentle,
+    Sassy,
+    Careful,
+    Quirky,
+}
+
+impl Nature {
+    pub fn natures() -> [Nature; 25] {
+        use Nature::*;
+        [
+            Hardy, Lonely, Brave, Adamant, Naughty, Bold, Docile, Relaxed, Impish, Lax, Timid,
+            Hasty, Serious, Jolly, Naive, Modest, Mild, Quiet, Bashful, Rash, Calm, Gentle, Sassy,
+            Careful, Quirky,
+        ]
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Nature> {
+        Nature::natures()
+            .iter()
+            .find(|nature| format!("{:?}", nature).eq_ignore_ascii_case(s))
+            .copied()
+    }
+
+    pub fn raised(&self) -> Option<Stat> {
+        let (raised, lowered) = self.grid();
+        if raised == lowered {
+            None
+        } else {
+            Some(NATURE_GRID[raised])
+        }
+    }
+
+    pub fn lowered(&self) -> Option<Stat> {
+        let (raised, lowered) = self.grid();
+        if raised == lowered {
+            None
+        } else {
+            Some(NATURE_GRID[lowered])
+        }
+    }
+
+    /// Stat modifier in percent.
+    pub fn modifier(&self, stat: Stat) -> u32 {
+        if self.raised() == Some(stat) {
+            110
+        } else if self.lowered() == Some(stat) {
+            90
+        } else {
+            100
+        }
+    }
+
+    /// Natures are laid out in a 5x5 grid of raised by lowered stat, with
+    /// the diagonal being neutral.
+    fn grid(&self) -> (usize, usize) {
+        let i = *self as usize;
+        (i / 5, i % 5)
+    }
+}
+
+/// A stat spread: base stats, IVs, EVs, level and nature.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Spread {
+    pub base: [u32; STAT_COUNT],
+    pub ivs: [u32; STAT_COUNT],
+    pub evs: [u32; STAT_COUNT],
+    pub level: u32,
+    pub nature: Nature,
+}
+
+impl Spread {
+    /// Level 100 with perfect IVs, no EVs and a neutral nature.
+    pub fn new(base: [u32; STAT_COUNT]) -> Self {
+        Self {
+            base,
+            ivs: [31; STAT_COUNT],
+            evs: [0; STAT_COUNT],
+            level: 100,
+            nature: Nature::default(),
+        }
+    }
+
+    pub fn level(mut self, level: u32) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn evs(mut self, evs: [u32; STAT_COUNT]) -> Self {
+        self.evs = evs;
+        self
+    }
+
+    pub fn ivs(mut self, ivs: [u32; STAT_COUNT]) -> Self {
+        self.ivs = ivs;
+        self
+    }
+
+    pub fn nature(mut self, nature: Nature) -> Self {
+        self.nature = nature;
+        self
+    }
+
+    pub fn stat(&self, stat: Stat) -> u32 {
+        let i = stat.index();
+        let raw = (2 * self.base[i] + self.ivs[i] + self.evs[i] / 4) * self.level / 100;
+        if stat == Stat::Hp {
+            // Shedinja always has 1 HP.
+            if self.base[i] == 1 {
+                return 1;
+            }
+            raw + self.level + 10
+        } else {
+            (raw + 5) * self.nature.modifier(stat) / 100
+        }
+    }
+
+    pub fn stats(&self) -> [u32; STAT_COUNT] {
+        let mut stats = [0; STAT_COUNT];
+        for stat in Stat::stats().iter() {
+            stats[stat.index()] = self.stat(*stat);
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nature_modifiers() {
+        assert_eq!(Nature::Adamant.raised(), Some(Stat::Attack));
+        assert_eq!(Nature::Adamant.lowered(), Some(Stat::SpAttack));
+        assert_eq!(Nature::Timid.raised(), Some(Stat::Speed));
+        assert_eq!(Nature::Timid.lowered(), Some(Stat::Attack));
+        assert_eq!(Nature::Brave.lowered(), Some(Stat::Speed));
+        assert_eq!(Nature::Calm.raised(), Some(Stat::SpDefense));
+        assert_eq!(Nature::Serious.raised(), None);
+        assert_eq!(Nature::from_str("jolly"), Some(Nature::Jolly));
+    }
+
+    #[test]
+    fn garchomp_stats() {
+        // Garchomp, level 100, 252 Atk / 4 SpD / 252 Spe, Jolly.
+        let spread = Spread::new([108, 130, 95, 80, 85, 102])
+            .evs([0, 252, 0, 0, 4, 252])
+            .nature(Nature::Jolly);
+        assert_eq!(spread.stats(), [357, 359, 226, 176, 207, 333]);
+    }
+
+    #[test]
+    fn level_50_stats() {
+        let spread = Spread::new([108, 130, 95, 80, 85, 102])
+            .level(50)
+            .evs([4, 252, 0, 0, 0, 252])
+            .nature(Nature::Jolly);
+        assert_eq!(spread.stats(), [184, 182, 115, 90, 105, 169]);
+    }
+}
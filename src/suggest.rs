@@ -0,0 +1,278 @@
+use crate::chart::Chart;
+use crate::generation::Generation;
+use crate::species::Pokedex;
+use crate::types::{BlockTypes, ChartType, Type};
+use crate::weakness::WeaknessReport;
+use itertools::Itertools;
+
+/// Ways to score a team defensively. Lower scores are better.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Scorer {
+    /// Sum of log2 of every multiplier every member takes, with immunities
+    /// counting as neutral. This is the `list_types` ranking.
+    LogRates,
+    /// Attacking types no member resists or is immune to.
+    Holes,
+    /// Per attacking type, weak members (4× counting twice) minus members
+    /// resisting or immune, summed over the types where that is positive.
+    Weaknesses,
+    /// Sum of every multiplier every member takes. This is the ranking of
+    /// the checked-in `data`, `data4` and `data5` files.
+    Multipliers,
+    /// log10 of `Multipliers`, as in the checked-in `more` file.
+    LogMultipliers,
+}
+
+impl Scorer {
+    pub fn scorers() -> [Scorer; 5] {
+        use Scorer::*;
+        [LogRates, Holes, Weaknesses, Multipliers, LogMultipliers]
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Scorer> {
+        Scorer::scorers()
+            .iter()
+            .find(|scorer| scorer.to_str().eq_ignore_ascii_case(s))
+            .copied()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        use Scorer::*;
+        match *self {
+            LogRates => "log",
+            Holes => "holes",
+            Weaknesses => "weaknesses",
+            Multipliers => "multipliers",
+            LogMultipliers => "log-multipliers",
+        }
+    }
+
+    pub fn score<T: ChartType, B: BlockTypes<T>>(&self, chart: &Chart<T>, team: &[B]) -> f64 {
+        match *self {
+            Scorer::LogRates => team
+                .iter()
+                .map(|member| {
+                    chart
+                        .blocks_rates(member)
+                        .into_iter()
+                        .fold(0.0, |a, (_, b)| a + if b == 0.0 { b } else { b.log2() })
+                })
+                .sum(),
+            Scorer::Holes => WeaknessReport::new(chart, team).holes().len() as f64,
+            Scorer::Weaknesses => WeaknessReport::new(chart, team)
+                .matchups
+                .iter()
+                .map(|m| {
+                    let weak = m.weak + 2 * m.very_weak;
+                    weak.saturating_sub(m.resist + m.immune) as f64
+                })
+                .sum(),
+            Scorer::Multipliers => team
+                .iter()
+                .map(|member| {
+                    chart
+                        .types()
+                        .iter()
+                        .map(|ty| chart.combat_n(ty, member))
+                        .sum::<f64>()
+                })
+                .sum(),
+            Scorer::LogMultipliers => Scorer::Multipliers.score(chart, team).log10(),
+        }
+    }
+}
+
+/// Every combination of exactly `size` distinct types of the chart, each
+/// scored alone and sorted best first. Ties keep the order of `combinations`.
+pub fn rank_typings<T: ChartType>(
+    chart: &Chart<T>,
+    size: usize,
+    scorer: Scorer,
+) -> Vec<(Vec<T>, f64)> {
+    chart
+        .types()
+        .iter()
+        .copied()
+        .combinations(size)
+        .map(|types| {
+            let score = scorer.score(chart, std::slice::from_ref(&types));
+            (types, score)
+        })
+        .sorted_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+        .collect()
+}
+
+/// A ranking in the `list_types` layout: a count line, then one
+/// `[A, B, C] = score` line per typing.
+pub fn format_ranking(ranking: &[(Vec<Type>, f64)]) -> String {
+    let mut out = format!("types: {}\n", ranking.len());
+    for (types, score) in ranking {
+        out += &format!("{:?} = {}\n", types, score);
+    }
+    out
+}
+
+/// Every mono and dual typing of the chart.
+pub fn typings<T: ChartType>(chart: &Chart<T>) -> Vec<Vec<T>> {
+    let types = chart.types();
+    types
+        .iter()
+        .map(|ty| vec![*ty])
+        .chain(
+            types
+                .iter()
+                .copied()
+                .tuple_combinations()
+                .map(|(a, b)| vec![a, b]),
+        )
+        .collect()
+}
+
+/// A candidate for the next team slot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate<T: ChartType = Type> {
+    pub types: Vec<T>,
+    /// Species with this typing, when ranking species.
+    pub species: Option<String>,
+    /// Score of the team with the candidate added.
+    pub score: f64,
+    /// How much the candidate lowers the team's score.
+    pub improvement: f64,
+    /// Attacking types no member resisted that the candidate resists or is
+    /// immune to.
+    pub patches: Vec<T>,
+    /// Attacking types the candidate is weak to that no other member
+    /// resists.
+    pub adds: Vec<T>,
+}
+
+/// Ranks every mono and dual typing as an addition to `team`, best first.
+pub fn suggest_typings<T: ChartType, B: BlockTypes<T>>(
+    chart: &Chart<T>,
+    team: &[B],
+    scorer: Scorer,
+) -> Vec<Candidate<T>> {
+    let candidates = typings(chart)
+        .into_iter()
+        .map(|types| (types, None))
+        .collect();
+    suggest(chart, team, scorer, candidates)
+}
+
+/// Ranks every species of the generation as an addition to `team`, best
+/// first.
+pub fn suggest_species<B: BlockTypes>(
+    chart: &Chart,
+    team: &[B],
+    scorer: Scorer,
+    dex: &Pokedex,
+    generation: Generation,
+) -> Vec<Candidate> {
+    let candidates = dex
+        .species()
+        .iter()
+        .filter(|species| species.generation <= generation)
+        .filter_map(|species| Some((species.types_in(generation)?, Some(species.name()))))
+        .collect();
+    suggest(chart, team, scorer, candidates)
+}
+
+fn suggest<T: ChartType, B: BlockTypes<T>>(
+    chart: &Chart<T>,
+    team: &[B],
+    scorer: Scorer,
+    candidates: Vec<(Vec<T>, Option<String>)>,
+) -> Vec<Candidate<T>> {
+    let typings: Vec<_> = team.iter().map(BlockTypes::block_types).collect();
+    let before = scorer.score(chart, &typings);
+    let report = WeaknessReport::new(chart, &typings);
+
+    let mut ranked: Vec<_> = candidates
+        .into_iter()
+        .map(|(types, species)| {
+            let mut with = typings.clone();
+            with.push(types.iter().collect());
+            let score = scorer.score(chart, &with);
+            let rates = chart.blocks_rates(&types);
+            let (patches, adds) =
+                report
+                    .matchups
+                    .iter()
+                    .fold((vec![], vec![]), |(mut patches, mut adds), m| {
+                        let rate = rates[&m.attack_type];
+                        if !m.is_covered() && rate < 1.0 {
+                            patches.push(m.attack_type);
+                        }
+                        if !m.is_covered() && rate > 1.0 {
+                            adds.push(m.attack_type);
+                        }
+                        (patches, adds)
+                    });
+            Candidate {
+                types,
+                species,
+                score,
+                improvement: before - score,
+                patches,
+                adds,
+            }
+        })
+        .collect();
+    ranked.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap());
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+
+    #[test]
+    fn typings_count() {
+        assert_eq!(typings(&Chart::builtin()).len(), 171);
+        assert_eq!(typings(&Chart::generation(Generation::Gen1)).len(), 120);
+    }
+
+    #[test]
+    fn rank_matches_list_types() {
+        let ranked = rank_typings(&Chart::builtin(), 3, Scorer::LogRates);
+        assert_eq!(ranked.len(), 816);
+        assert_eq!(ranked[0], (vec![Flying, Steel, Fire], -12.0));
+    }
+
+    #[test]
+    fn rank_exact_size() {
+        let chart = Chart::builtin();
+        assert_eq!(rank_typings(&chart, 1, Scorer::LogRates).len(), 18);
+        let pairs = rank_typings(&chart, 2, Scorer::LogRates);
+        assert_eq!(pairs.len(), 153);
+        assert!(pairs.iter().all(|(types, _)| types.len() == 2));
+    }
+
+    #[test]
+    fn suggest_patches_holes() {
+        let team = [vec![&Fire], vec![&Water], vec![&Grass]];
+        let candidates = suggest_typings(&Chart::builtin(), &team, Scorer::Holes);
+        let best = &candidates[0];
+        assert!(best.improvement > 0.0);
+        assert!(best.patches.len() as f64 >= best.improvement);
+        let normal = candidates.iter().find(|c| c.types == vec![Normal]).unwrap();
+        assert_eq!(normal.patches, vec![Ghost]);
+        assert_eq!(normal.adds, vec![Fight]);
+    }
+
+    #[test]
+    fn suggest_from_species() {
+        let team = [vec![&Fire], vec![&Water], vec![&Grass]];
+        let candidates = suggest_species(
+            &Chart::builtin(),
+            &team,
+            Scorer::Weaknesses,
+            Pokedex::bundled(),
+            Generation::Gen9,
+        );
+        assert!(candidates.iter().all(|c| c.species.is_some()));
+        assert!(candidates[0].score <= candidates[candidates.len() - 1].score);
+    }
+}
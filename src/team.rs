@@ -0,0 +1,171 @@
+use crate::chart::Chart;
+use crate::damage::Pokemon;
+use crate::moves::MoveData;
+use crate::species::Species;
+use crate::stats::Spread;
+use crate::types::Type;
+use crate::weakness::WeaknessReport;
+
+/// One team member: a species with its set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub nickname: Option<String>,
+    pub species: Species,
+    pub item: Option<String>,
+    pub ability: Option<String>,
+    pub tera: Option<Type>,
+    pub spread: Spread,
+    pub moves: Vec<MoveData>,
+}
+
+impl Member {
+    /// A level 100 member with no set.
+    pub fn new(species: Species) -> Self {
+        Self {
+            nickname: None,
+            spread: Spread::new(species.base_stats),
+            species,
+            item: None,
+            ability: None,
+            tera: None,
+            moves: Vec::new(),
+        }
+    }
+
+    /// The nickname, or the species name when there is none.
+    pub fn name(&self) -> String {
+        self.nickname.clone().unwrap_or_else(|| self.species.name())
+    }
+
+    pub fn types(&self) -> &[Type] {
+        &self.species.types
+    }
+
+    /// Type of a move when this member uses it; Tera Blast takes the Tera
+    /// type.
+    pub fn move_type(&self, mv: &MoveData) -> Type {
+        match self.tera {
+            Some(tera) if mv.identifier == "tera-blast" => tera,
+            _ => mv.ty,
+        }
+    }
+
+    /// Types of the member's damaging moves, without duplicates.
+    pub fn attack_types(&self) -> Vec<Type> {
+        let mut types = Vec::new();
+        for mv in self.moves.iter().filter(|mv| !mv.is_status()) {
+            let ty = self.move_type(mv);
+            if !types.contains(&ty) {
+                types.push(ty);
+            }
+        }
+        types
+    }
+
+    /// The member for the damage calculator.
+    pub fn pokemon(&self) -> Pokemon {
+        let types: Vec<_> = self.types().iter().collect();
+        let mut pokemon = Pokemon::new(&types, &self.spread);
+        if self
+            .ability
+            .as_deref()
+            .is_some_and(|ability| ability.eq_ignore_ascii_case("adaptability"))
+        {
+            pokemon = pokemon.adaptability();
+        }
+        pokemon
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Team {
+    pub members: Vec<Member>,
+}
+
+impl Team {
+    pub fn new(members: Vec<Member>) -> Self {
+        Self { members }
+    }
+
+    /// Typing of every member.
+    pub fn typings(&self) -> Vec<Vec<&Type>> {
+        self.members
+            .iter()
+            .map(|member| member.types().iter().collect())
+            .collect()
+    }
+
+    pub fn weakness_report(&self, chart: &Chart) -> WeaknessReport {
+        WeaknessReport::new(chart, &self.typings())
+    }
+
+    /// Types of every damaging move on the team, without duplicates.
+    pub fn attack_types(&self) -> Vec<Type> {
+        let mut types = Vec::new();
+        for ty in self.members.iter().flat_map(Member::attack_types) {
+            if !types.contains(&ty) {
+                types.push(ty);
+            }
+        }
+        types
+    }
+
+    /// Best multiplier the team's moves reach against each type of the
+    /// chart, with the attacking type that reaches it.
+    pub fn coverage(&self, chart: &Chart) -> Vec<(Type, Option<Type>, f64)> {
+        let attack_types = self.attack_types();
+        chart
+            .types()
+            .iter()
+            .map(|block_type| {
+                attack_types
+                    .iter()
+                    .map(|attack_type| {
+                        (
+                            *block_type,
+                            Some(*attack_type),
+                            chart.combat1(attack_type, block_type),
+                        )
+                    })
+                    .fold((*block_type, None, 0.0), |best, candidate| {
+                        if best.1.is_none() || candidate.2 > best.2 {
+                            candidate
+                        } else {
+                            best
+                        }
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::Movedex;
+    use crate::species::Pokedex;
+    use crate::types::Type::*;
+
+    #[test]
+    fn attack_types_and_coverage() {
+        let moves = Movedex::bundled();
+        let mut garchomp = Member::new(Pokedex::bundled().get("Garchomp").unwrap().clone());
+        garchomp.tera = Some(Steel);
+        garchomp.moves = ["Earthquake", "Dragon Claw", "Swords Dance", "Tera Blast"]
+            .iter()
+            .map(|name| moves.get(name).unwrap())
+            .collect();
+        assert_eq!(garchomp.name(), "Garchomp");
+        assert_eq!(garchomp.attack_types(), vec![Ground, Dragon, Steel]);
+
+        let team = Team::new(vec![garchomp]);
+        let coverage = team.coverage(&Chart::builtin());
+        assert!(coverage.contains(&(Fire, Some(Ground), 2.0)));
+        assert!(coverage.contains(&(Fairy, Some(Steel), 2.0)));
+        assert!(coverage.contains(&(Flying, Some(Dragon), 1.0)));
+        assert_eq!(
+            Team::default().coverage(&Chart::builtin())[0],
+            (Normal, None, 0.0)
+        );
+    }
+}
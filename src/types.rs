@@ -1,9 +1,10 @@
-use itertools::Itertools;
+use crate::ruleset::{Ruleset, Stacking};
 use lazy_static::lazy_static;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::hash::Hash;
 
-const TYPE_COUNT: usize = 18;
+pub(crate) const TYPE_COUNT: usize = 18;
 
 #[derive(Debug, Copy, Clone, PartialEq, Hash)]
 pub enum Type {
@@ -29,11 +30,118 @@ pub enum Type {
 
 impl Eq for Type {}
 
+/// A type a `Chart` can be built over: the built-in `Type`, or a
+/// `CustomType` for charts with types the enum does not have.
+pub trait ChartType: Copy + Eq + Hash + Debug {
+    /// Position of the type in a chart's storage.
+    fn index(&self) -> usize;
+
+    /// The built-in type this stands for, if any.
+    fn builtin(&self) -> Option<Type>;
+}
+
+impl ChartType for Type {
+    fn index(&self) -> usize {
+        *self as usize
+    }
+
+    fn builtin(&self) -> Option<Type> {
+        Some(*self)
+    }
+}
+
+/// A type of a custom chart. Indices below `TYPE_COUNT` stand for the
+/// built-in types in `Type::types()` order; the rest are added with
+/// `Chart::add_type`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct CustomType(pub u16);
+
+impl ChartType for CustomType {
+    fn index(&self) -> usize {
+        self.0 as usize
+    }
+
+    fn builtin(&self) -> Option<Type> {
+        Type::types().get(self.index()).copied()
+    }
+}
+
+impl From<Type> for CustomType {
+    fn from(ty: Type) -> Self {
+        CustomType(ty as u16)
+    }
+}
+
+impl From<&Type> for CustomType {
+    fn from(ty: &Type) -> Self {
+        CustomType(*ty as u16)
+    }
+}
+
+/// Anything that can be matched up as the defending side: a slice of types
+/// or a battler's current typing.
+pub trait BlockTypes<T = Type> {
+    fn block_types(&self) -> Vec<&T>;
+}
+
+impl<T: ChartType> BlockTypes<T> for [&T] {
+    fn block_types(&self) -> Vec<&T> {
+        self.to_vec()
+    }
+}
+
+impl<T: ChartType, const N: usize> BlockTypes<T> for [&T; N] {
+    fn block_types(&self) -> Vec<&T> {
+        self.to_vec()
+    }
+}
+
+impl<T: ChartType> BlockTypes<T> for Vec<&T> {
+    fn block_types(&self) -> Vec<&T> {
+        self.clone()
+    }
+}
+
+impl<T: ChartType> BlockTypes<T> for [T] {
+    fn block_types(&self) -> Vec<&T> {
+        self.iter().collect()
+    }
+}
+
+impl<T: ChartType> BlockTypes<T> for Vec<T> {
+    fn block_types(&self) -> Vec<&T> {
+        self.iter().collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Localization {
     English,
     Japanese,
 }
 
+impl Localization {
+    pub fn localizations() -> [Localization; 2] {
+        [Localization::English, Localization::Japanese]
+    }
+
+    /// Language identifier as used for chart type names, e.g. "ja".
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Localization::English => "en",
+            Localization::Japanese => "ja",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Localization> {
+        match code {
+            "en" => Some(Localization::English),
+            "ja" | "ja-Hrkt" => Some(Localization::Japanese),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Hash, Clone)]
 struct Combat<'a> {
     attack: &'a Type,
@@ -44,10 +152,7 @@ impl<'a> Eq for Combat<'a> {}
 
 impl<'a> Combat<'a> {
     fn new(attack: &'a Type, block: &'a Type) -> Self {
-        Self {
-            attack: attack,
-            block: block,
-        }
+        Self { attack, block }
     }
 }
 
@@ -60,6 +165,7 @@ impl Type {
         ]
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Type> {
         for ty in Type::types().iter() {
             if ty.to_str(&Localization::English) == s {
@@ -69,34 +175,42 @@ impl Type {
         None
     }
 
-    pub fn blocks_rates(block_types: &[&Type]) -> HashMap<Type, f64> {
-        block_types
+    /// Parses a type name in any language, e.g. "炎" in Japanese.
+    pub fn from_localized(s: &str, l10n: &Localization) -> Option<Type> {
+        Type::types()
+            .iter()
+            .find(|ty| ty.to_str(l10n) == s)
+            .copied()
+    }
+
+    pub fn blocks_rates<B: BlockTypes + ?Sized>(block_types: &B) -> HashMap<Type, f64> {
+        let block_types = block_types.block_types();
+        Type::types()
             .iter()
-            .flat_map(|block_type| block_type.block_rates())
-            .into_group_map()
-            .into_iter()
-            .map(|(key, v)| (key.clone(), v.iter().fold(1.0_f64, |acc, x| acc * x)))
+            .map(|attack_type| (*attack_type, Type::combat_n(attack_type, &block_types)))
             .collect()
     }
 
     pub fn combat1(atack_type: &Type, block_type: &Type) -> f64 {
-        let combat = Combat::new(&atack_type, &block_type);
+        let combat = Combat::new(atack_type, block_type);
         let r = TYPE_EFFECT.get(&combat).unwrap();
         *r
     }
 
-    pub fn combat_n(attack_type: &Type, block_types: &[&Type]) -> f64 {
-        block_types
+    pub fn combat_n<B: BlockTypes + ?Sized>(attack_type: &Type, block_types: &B) -> f64 {
+        let relations: Vec<_> = block_types
+            .block_types()
             .iter()
             .map(|block_type| Type::combat1(attack_type, block_type))
-            .fold(1.0, |a, b| a * b)
+            .collect();
+        Ruleset::Mainline.stack(&relations)
     }
 
     pub fn attack_rate1(&self, block_type: &Type) -> f64 {
         Type::combat1(self, block_type)
     }
 
-    pub fn attack_rate_n(&self, block_types: &[&Type]) -> f64 {
+    pub fn attack_rate_n<B: BlockTypes + ?Sized>(&self, block_types: &B) -> f64 {
         Self::combat_n(self, block_types)
     }
 
@@ -114,10 +228,43 @@ impl Type {
             .collect()
     }
 
+    /// Lowercase identifier used by data files such as PokeAPI and Showdown,
+    /// e.g. "fighting".
+    pub fn identifier(&self) -> &'static str {
+        use Type::*;
+        match *self {
+            Normal => "normal",
+            Fight => "fighting",
+            Flying => "flying",
+            Poison => "poison",
+            Ground => "ground",
+            Rock => "rock",
+            Bug => "bug",
+            Ghost => "ghost",
+            Steel => "steel",
+            Fire => "fire",
+            Water => "water",
+            Grass => "grass",
+            Electric => "electric",
+            Psychic => "psychic",
+            Ice => "ice",
+            Dragon => "dragon",
+            Dark => "dark",
+            Fairy => "fairy",
+        }
+    }
+
+    pub fn from_identifier(s: &str) -> Option<Type> {
+        Type::types()
+            .iter()
+            .find(|ty| ty.identifier().eq_ignore_ascii_case(s))
+            .copied()
+    }
+
     pub fn to_str(&self, l10n: &Localization) -> &'static str {
-        match l10n {
-            &Localization::Japanese => TYPE_STR_JAPANESE.get(&self).unwrap(),
-            &Localization::English => TYPE_STR_ENGLISH.get(&self).unwrap(),
+        match *l10n {
+            Localization::Japanese => TYPE_STR_JAPANESE.get(self).unwrap(),
+            Localization::English => TYPE_STR_ENGLISH.get(self).unwrap(),
         }
     }
 }
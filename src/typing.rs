@@ -0,0 +1,244 @@
+use crate::types::{BlockTypes, Type};
+
+const MAX_TYPES: usize = 3;
+
+/// The current types of a battler, including types added, replaced or
+/// removed by moves during battle.
+///
+/// Operations return whether the move succeeded, following the in-game
+/// failure conditions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typing {
+    types: Vec<Type>,
+    added: Option<Type>,
+    tera: Option<Type>,
+}
+
+impl Typing {
+    pub fn new(types: &[&Type]) -> Self {
+        let mut base = Vec::new();
+        for ty in types {
+            if !base.contains(*ty) {
+                base.push(**ty);
+            }
+        }
+        base.truncate(MAX_TYPES);
+        Self {
+            types: base,
+            added: None,
+            tera: None,
+        }
+    }
+
+    /// Current types in order, with the added type last.
+    pub fn types(&self) -> Vec<&Type> {
+        if let Some(tera) = &self.tera {
+            return vec![tera];
+        }
+        self.types.iter().chain(self.added.iter()).collect()
+    }
+
+    pub fn has(&self, ty: &Type) -> bool {
+        self.types().contains(&ty)
+    }
+
+    pub fn is_typeless(&self) -> bool {
+        self.types().is_empty()
+    }
+
+    pub fn added(&self) -> Option<&Type> {
+        self.added.as_ref()
+    }
+
+    pub fn is_terastallized(&self) -> bool {
+        self.tera.is_some()
+    }
+
+    /// Forest's Curse and Trick-or-Treat. The new type replaces any type
+    /// added earlier, and fails if the battler already has it.
+    pub fn add_type(&mut self, ty: &Type) -> bool {
+        if self.is_terastallized() || self.has(ty) {
+            return false;
+        }
+        if self.added.is_none() && self.types.len() >= MAX_TYPES {
+            return false;
+        }
+        self.added = Some(*ty);
+        true
+    }
+
+    pub fn forests_curse(&mut self) -> bool {
+        self.add_type(&Type::Grass)
+    }
+
+    pub fn trick_or_treat(&mut self) -> bool {
+        self.add_type(&Type::Ghost)
+    }
+
+    /// Replaces every type, including an added one. Fails if the battler
+    /// already has exactly the given typing.
+    pub fn replace_types(&mut self, types: &[&Type]) -> bool {
+        let replaced = Typing::new(types);
+        if self.is_terastallized() || replaced.types.is_empty() || self.types() == replaced.types()
+        {
+            return false;
+        }
+        self.types = replaced.types;
+        self.added = None;
+        true
+    }
+
+    pub fn soak(&mut self) -> bool {
+        self.replace_types(&[&Type::Water])
+    }
+
+    pub fn magic_powder(&mut self) -> bool {
+        self.replace_types(&[&Type::Psychic])
+    }
+
+    pub fn conversion(&mut self, ty: &Type) -> bool {
+        if self.has(ty) {
+            return false;
+        }
+        self.replace_types(&[ty])
+    }
+
+    /// Copies the target's current types, including its added type, which
+    /// stays the one a later Forest's Curse or Trick-or-Treat replaces.
+    /// Fails against a typeless target.
+    pub fn reflect_type(&mut self, target: &Typing) -> bool {
+        if self.is_terastallized() || target.is_typeless() {
+            return false;
+        }
+        match target.tera {
+            Some(tera) => {
+                self.types = vec![tera];
+                self.added = None;
+            }
+            None => {
+                self.types = target.types.clone();
+                self.added = target.added;
+            }
+        }
+        true
+    }
+
+    /// Burn Up and Double Shock. Removing the last type leaves the battler
+    /// typeless, which takes neutral damage from every type.
+    pub fn remove_type(&mut self, ty: &Type) -> bool {
+        if self.is_terastallized() || !self.has(ty) {
+            return false;
+        }
+        self.types.retain(|t| t != ty);
+        if self.added == Some(*ty) {
+            self.added = None;
+        }
+        true
+    }
+
+    pub fn burn_up(&mut self) -> bool {
+        self.remove_type(&Type::Fire)
+    }
+
+    pub fn double_shock(&mut self) -> bool {
+        self.remove_type(&Type::Electric)
+    }
+
+    /// Terastallizing sets the defensive typing to the Tera type and locks
+    /// it against further changes.
+    pub fn terastallize(&mut self, ty: &Type) -> bool {
+        if self.is_terastallized() {
+            return false;
+        }
+        self.tera = Some(*ty);
+        true
+    }
+}
+
+impl BlockTypes for Typing {
+    fn block_types(&self) -> Vec<&Type> {
+        self.types()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+
+    #[test]
+    fn added_type() {
+        let mut typing = Typing::new(&[&Water, &Ground]);
+        assert!(typing.forests_curse());
+        assert_eq!(typing.types(), vec![&Water, &Ground, &Grass]);
+        assert_eq!(Type::combat_n(&Grass, &typing), 2.0);
+
+        assert!(typing.trick_or_treat());
+        assert_eq!(typing.types(), vec![&Water, &Ground, &Ghost]);
+        assert!(!typing.trick_or_treat());
+        assert_eq!(Type::combat_n(&Normal, &typing), 0.0);
+    }
+
+    #[test]
+    fn added_type_fails_on_existing_type() {
+        let mut typing = Typing::new(&[&Grass, &Poison]);
+        assert!(!typing.forests_curse());
+        assert_eq!(typing.added(), None);
+    }
+
+    #[test]
+    fn replace_types() {
+        let mut typing = Typing::new(&[&Fire, &Flying]);
+        typing.trick_or_treat();
+        assert!(typing.soak());
+        assert_eq!(typing.types(), vec![&Water]);
+        assert!(!typing.soak());
+
+        assert!(typing.magic_powder());
+        assert_eq!(typing.types(), vec![&Psychic]);
+
+        assert!(!typing.conversion(&Psychic));
+        assert!(typing.conversion(&Steel));
+        assert_eq!(typing.types(), vec![&Steel]);
+    }
+
+    #[test]
+    fn reflect_type_copies_added_type() {
+        let mut target = Typing::new(&[&Normal]);
+        target.trick_or_treat();
+        let mut typing = Typing::new(&[&Fire]);
+        assert!(typing.reflect_type(&target));
+        assert_eq!(typing.types(), vec![&Normal, &Ghost]);
+        assert!(typing.forests_curse());
+        assert_eq!(typing.types(), vec![&Normal, &Grass]);
+
+        let mut typeless = Typing::new(&[&Fire]);
+        typeless.burn_up();
+        assert!(!typing.reflect_type(&typeless));
+    }
+
+    #[test]
+    fn remove_type() {
+        let mut typing = Typing::new(&[&Fire]);
+        assert!(typing.burn_up());
+        assert!(typing.is_typeless());
+        assert!(!typing.burn_up());
+        assert_eq!(Type::combat_n(&Water, &typing), 1.0);
+        assert!(Type::blocks_rates(&typing).values().all(|r| *r == 1.0));
+
+        let mut typing = Typing::new(&[&Electric, &Flying]);
+        typing.forests_curse();
+        assert!(typing.double_shock());
+        assert_eq!(typing.types(), vec![&Flying, &Grass]);
+    }
+
+    #[test]
+    fn tera_locks_typing() {
+        let mut typing = Typing::new(&[&Dragon, &Ground]);
+        assert!(typing.terastallize(&Steel));
+        assert_eq!(typing.types(), vec![&Steel]);
+        assert!(!typing.soak());
+        assert!(!typing.forests_curse());
+        assert_eq!(Type::combat_n(&Ice, &typing), 0.5);
+    }
+}
@@ -0,0 +1,331 @@
+use crate::balance::dominated;
+use crate::chart::Chart;
+use crate::showdown::{
+    code_rate, from_typechart_custom, is_status_key, parse_typechart, typechart_entries,
+    TypechartEntry, Value,
+};
+use crate::types::{CustomType, Type};
+use std::collections::HashMap;
+use std::fmt;
+
+/// How bad a problem in a chart file is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Legal, but unlike anything in an official chart, e.g. a type
+    /// without weaknesses.
+    Unusual,
+    /// A mistake: the chart cannot be loaded as written, or does not mean
+    /// what was written.
+    Invalid,
+}
+
+impl Severity {
+    pub fn to_str(&self) -> &'static str {
+        match *self {
+            Severity::Unusual => "unusual",
+            Severity::Invalid => "invalid",
+        }
+    }
+}
+
+/// A problem in a chart file, with the 1-based line it was found on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub severity: Severity,
+    pub line: usize,
+    pub message: String,
+}
+
+impl Problem {
+    fn invalid(line: usize, message: String) -> Self {
+        Self {
+            severity: Severity::Invalid,
+            line,
+            message,
+        }
+    }
+
+    fn unusual(line: usize, message: String) -> Self {
+        Self {
+            severity: Severity::Unusual,
+            line,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {}: {}",
+            self.line,
+            self.severity.to_str(),
+            self.message
+        )
+    }
+}
+
+/// Every problem in a Showdown type chart, by line. Unusual matchups are
+/// only looked for once the chart loads.
+pub fn validate_typechart(text: &str) -> Vec<Problem> {
+    let root = match parse_typechart(text) {
+        Ok(root) => root,
+        Err(e) => return vec![Problem::invalid(e.line, e.message)],
+    };
+    let entries = match typechart_entries(&root) {
+        Ok(entries) => entries,
+        Err(e) => return vec![Problem::invalid(e.line, e.message)],
+    };
+
+    let mut problems = Vec::new();
+    // Identifiers of the chart's types, as `from_typechart_custom` builds it.
+    let mut types: Vec<String> = if entries.iter().all(|entry| entry.inherit) {
+        Type::types()
+            .iter()
+            .map(|ty| ty.identifier().to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut defined: HashMap<String, usize> = HashMap::new();
+    for entry in &entries {
+        let id = entry.id.to_lowercase();
+        match defined.get(&id) {
+            Some(first) => problems.push(Problem::invalid(
+                entry.line,
+                format!("`{}` is defined twice, first on line {}", entry.id, first),
+            )),
+            None => {
+                defined.insert(id.clone(), entry.line);
+                if !types.contains(&id) {
+                    types.push(id);
+                }
+            }
+        }
+    }
+
+    for entry in &entries {
+        problems.extend(check_cells(entry, &types));
+    }
+
+    if problems.iter().all(|p| p.severity != Severity::Invalid) {
+        match from_typechart_custom(text, &Chart::builtin()) {
+            Ok(chart) => problems.extend(check_matchups(&chart, &entries)),
+            Err(e) => problems.push(Problem::invalid(e.line, e.message)),
+        }
+    }
+    problems.sort_by_key(|p| p.line);
+    problems
+}
+
+/// Duplicated, unknown, missing and malformed `damageTaken` codes of one
+/// defending type.
+fn check_cells(entry: &TypechartEntry, types: &[String]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut given: HashMap<String, usize> = HashMap::new();
+    for (key, line, code) in &entry.taken {
+        let name = key.to_lowercase();
+        if let Some(first) = given.get(&name) {
+            problems.push(Problem::invalid(
+                *line,
+                format!(
+                    "`{}` is given twice for `{}`, first on line {}",
+                    key, entry.id, first
+                ),
+            ));
+            continue;
+        }
+        given.insert(name.clone(), *line);
+        if is_status_key(key) {
+            continue;
+        }
+        if !types.contains(&name) {
+            problems.push(Problem::unusual(
+                *line,
+                format!("`{}` is not a type of the chart and is ignored", key),
+            ));
+            continue;
+        }
+        match code {
+            Value::Number(code) => match code_rate(*code) {
+                None => problems.push(Problem::invalid(
+                    *line,
+                    format!(
+                        "invalid damage code {} for `{}`: expected 0 (neutral), 1 (weak), \
+                         2 (resisted) or 3 (immune)",
+                        code, key
+                    ),
+                )),
+                Some(rate) if rate == 0.0 && name == entry.id.to_lowercase() => problems.push(
+                    Problem::invalid(*line, format!("`{}` is immune to itself", entry.id)),
+                ),
+                Some(_) => {}
+            },
+            _ => problems.push(Problem::invalid(
+                *line,
+                format!("damage code for `{}` is not a number", key),
+            )),
+        }
+    }
+
+    // Inheriting built-in types keep the built-in codes for built-in
+    // attackers.
+    let inherited = |ty: &str| {
+        entry.inherit
+            && Type::from_identifier(entry.id).is_some()
+            && Type::from_identifier(ty).is_some()
+    };
+    let missing: Vec<_> = types
+        .iter()
+        .filter(|ty| !given.contains_key(*ty) && !inherited(ty))
+        .map(|ty| format!("`{}`", ty))
+        .collect();
+    if !missing.is_empty() {
+        problems.push(Problem::invalid(
+            entry.line,
+            format!(
+                "`{}` has no damage code for {}",
+                entry.id,
+                missing.join(", ")
+            ),
+        ));
+    }
+    problems
+}
+
+/// Types without weaknesses or defenses, one-sided immunities and
+/// dominated types of a loaded chart.
+fn check_matchups(chart: &Chart<CustomType>, entries: &[TypechartEntry]) -> Vec<Problem> {
+    let entry = |ty: &CustomType| {
+        let id = chart.identifier(ty);
+        entries
+            .iter()
+            .find(|entry| entry.id.eq_ignore_ascii_case(&id))
+    };
+    let entry_line = |ty: &CustomType| entry(ty).map_or(1, |entry| entry.line);
+    let cell_line = |attack_type: &CustomType, block_type: &CustomType| {
+        let id = chart.identifier(attack_type);
+        entry(block_type)
+            .and_then(|entry| {
+                entry
+                    .taken
+                    .iter()
+                    .find(|(key, _, _)| key.eq_ignore_ascii_case(&id))
+                    .map(|(_, line, _)| *line)
+            })
+            .unwrap_or_else(|| entry_line(block_type))
+    };
+
+    let mut problems = Vec::new();
+    for ty in chart.types() {
+        let taken: Vec<_> = chart
+            .types()
+            .iter()
+            .map(|a| chart.relation(a, ty))
+            .collect();
+        if !taken.iter().any(|rate| *rate > 1.0) {
+            problems.push(Problem::unusual(
+                entry_line(ty),
+                format!("`{}` has no weaknesses", chart.identifier(ty)),
+            ));
+        }
+        if !taken.iter().any(|rate| *rate < 1.0) {
+            problems.push(Problem::unusual(
+                entry_line(ty),
+                format!(
+                    "`{}` has no resistances or immunities",
+                    chart.identifier(ty)
+                ),
+            ));
+        }
+        // Official immunities are mutual, as Normal and Ghost, or answered
+        // with at least a neutral hit.
+        for attack_type in chart.types().iter().filter(|a| *a != ty) {
+            let back = chart.relation(ty, attack_type);
+            if chart.relation(attack_type, ty) == 0.0 && back > 0.0 && back < 1.0 {
+                problems.push(Problem::unusual(
+                    cell_line(attack_type, ty),
+                    format!(
+                        "asymmetric immunity: `{}` is immune to `{}`, which also resists it",
+                        chart.identifier(ty),
+                        chart.identifier(attack_type)
+                    ),
+                ));
+            }
+        }
+    }
+    for (ty, by) in dominated(chart) {
+        problems.push(Problem::unusual(
+            entry_line(&ty),
+            format!(
+                "`{}` is dominated by `{}`: it hits nothing harder and takes nothing less",
+                chart.identifier(&ty),
+                chart.identifier(&by)
+            ),
+        ));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::Generation;
+    use crate::showdown::to_typechart_ts;
+
+    #[test]
+    fn official_charts_are_clean() {
+        for generation in Generation::generations().iter() {
+            let ts = to_typechart_ts(&Chart::generation(*generation));
+            assert_eq!(validate_typechart(&ts), vec![], "{:?}", generation);
+        }
+    }
+
+    #[test]
+    fn reports_problems() {
+        let text = "{\n\
+                    \x20 fire: {\n\
+                    \x20   damageTaken: {Fire: 3, Water: 1.5, Fier: 1, Water: 2},\n\
+                    \x20 },\n\
+                    \x20 water: {damageTaken: {Fire: 2}},\n\
+                    \x20 fire: {damageTaken: {}},\n\
+                    }\n";
+        let problems: Vec<_> = validate_typechart(text)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "line 3: invalid: `fire` is immune to itself",
+                "line 3: invalid: invalid damage code 1.5 for `Water`: expected 0 (neutral), \
+                 1 (weak), 2 (resisted) or 3 (immune)",
+                "line 3: unusual: `Fier` is not a type of the chart and is ignored",
+                "line 3: invalid: `Water` is given twice for `fire`, first on line 3",
+                "line 5: invalid: `water` has no damage code for `water`",
+                "line 6: invalid: `fire` is defined twice, first on line 2",
+                "line 6: invalid: `fire` has no damage code for `fire`, `water`",
+            ]
+        );
+    }
+
+    #[test]
+    fn unusual_matchups() {
+        let text = "{\n\
+                    \x20 fire: {damageTaken: {Fire: 1, Water: 2}},\n\
+                    \x20 water: {damageTaken: {Fire: 3, Water: 0}},\n\
+                    }\n";
+        let problems = validate_typechart(text);
+        assert!(problems.iter().all(|p| p.severity == Severity::Unusual));
+        let messages: Vec<_> = problems
+            .iter()
+            .map(|p| (p.line, p.message.as_str()))
+            .collect();
+        assert!(messages.contains(&(3, "`water` has no weaknesses")));
+        assert!(messages.contains(&(
+            3,
+            "asymmetric immunity: `water` is immune to `fire`, which also resists it"
+        )));
+    }
+}
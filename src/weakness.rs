@@ -0,0 +1,112 @@
+use crate::chart::Chart;
+use crate::types::{BlockTypes, ChartType, Type};
+
+/// How the members of a team take one attacking type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matchup<T: ChartType = Type> {
+    pub attack_type: T,
+    /// Members taking 2× (or anything between 1× and 4×).
+    pub weak: usize,
+    /// Members taking 4× or more.
+    pub very_weak: usize,
+    pub neutral: usize,
+    /// Members taking less than 1×, including 0.25×.
+    pub resist: usize,
+    pub immune: usize,
+}
+
+impl<T: ChartType> Matchup<T> {
+    /// Members weak to the type, 4× weak ones included.
+    pub fn weak_total(&self) -> usize {
+        self.weak + self.very_weak
+    }
+
+    /// Whether some member resists or is immune to the type.
+    pub fn is_covered(&self) -> bool {
+        self.resist + self.immune > 0
+    }
+}
+
+/// Per attacking type counts of how the members of a team take it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeaknessReport<T: ChartType = Type> {
+    pub matchups: Vec<Matchup<T>>,
+}
+
+impl<T: ChartType> WeaknessReport<T> {
+    pub fn new<B: BlockTypes<T>>(chart: &Chart<T>, team: &[B]) -> Self {
+        let matchups = chart
+            .types()
+            .iter()
+            .map(|attack_type| {
+                let mut matchup = Matchup {
+                    attack_type: *attack_type,
+                    weak: 0,
+                    very_weak: 0,
+                    neutral: 0,
+                    resist: 0,
+                    immune: 0,
+                };
+                for member in team {
+                    let rate = chart.combat_n(attack_type, member);
+                    if rate == 0.0 {
+                        matchup.immune += 1;
+                    } else if rate < 1.0 {
+                        matchup.resist += 1;
+                    } else if rate >= 4.0 {
+                        matchup.very_weak += 1;
+                    } else if rate > 1.0 {
+                        matchup.weak += 1;
+                    } else {
+                        matchup.neutral += 1;
+                    }
+                }
+                matchup
+            })
+            .collect();
+        Self { matchups }
+    }
+
+    pub fn get(&self, attack_type: &T) -> Option<&Matchup<T>> {
+        self.matchups.iter().find(|m| m.attack_type == *attack_type)
+    }
+
+    /// Types that at least `threshold` members are weak to.
+    pub fn stacked(&self, threshold: usize) -> Vec<&Matchup<T>> {
+        self.matchups
+            .iter()
+            .filter(|m| m.weak_total() >= threshold)
+            .collect()
+    }
+
+    /// Types no member resists or is immune to.
+    pub fn holes(&self) -> Vec<&Matchup<T>> {
+        self.matchups.iter().filter(|m| !m.is_covered()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type::*;
+
+    #[test]
+    fn counts() {
+        let team = [
+            vec![&Fire],
+            vec![&Rock, &Ground],
+            vec![&Electric],
+            vec![&Grass, &Ice],
+        ];
+        let report = WeaknessReport::new(&Chart::builtin(), &team);
+        let water = report.get(&Water).unwrap();
+        assert_eq!((water.weak, water.very_weak, water.neutral), (1, 1, 1));
+        assert_eq!((water.resist, water.immune), (1, 0));
+        assert_eq!(report.get(&Electric).unwrap().immune, 1);
+
+        let stacked: Vec<_> = report.stacked(3).iter().map(|m| m.attack_type).collect();
+        assert_eq!(stacked, vec![Ground]);
+        let holes: Vec<_> = report.holes().iter().map(|m| m.attack_type).collect();
+        assert_eq!(holes, vec![Fight, Ghost, Psychic, Dragon, Dark]);
+    }
+}